@@ -0,0 +1,81 @@
+use crate::consts::UNTAGGED;
+
+use git2::{Repository, Commit, Oid, Error};
+use regex::Regex;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+// splits a commit message at the first colon into 'data' (type of commit and contributors) and the message
+pub fn split_message<'a>(commit: &'a Commit) -> (&'a str, &'a str) {
+    match commit.message() {
+        Some(commit_msg) => match commit_msg.split_once(':') {
+            Some((data, msg)) => (data, msg),
+            _ => ("", commit_msg),
+        },
+        None => ("", ""),  // probably shouldn't end up here even if there is no colon
+    }
+}
+
+// works out who gets credit for a commit (before any exclusions) - either the names in the commit message
+// when using autogenerated aliases, or the config aliases the committer belongs to, or just the committer
+pub fn get_authors(commit: &Commit, config_map: &HashMap<String, Vec<String>>, options: &[bool], regex: &Regex) -> Vec<String> {
+    let mut authors = vec![];
+    if options[5] {  // using autogenerated config with commit message data
+        match regex.captures(split_message(commit).0) {
+            Some(captures) => {
+                for author in captures.iter().skip(1).flatten() {
+                    authors.push(author.as_str().to_string());
+                }
+            },
+            // no contributors listed in the expected format
+            // choose how to deal with this - maybe ignore or have an unknown
+            None => authors.push(UNTAGGED.to_string()),
+        }
+        return authors;
+    }
+
+    let author_name = match commit.committer().name() {  // get author of current commit
+        Some(name) => name.to_string(),
+        None => "ERROR".to_string(),
+    };
+    if !options[2] {  // only do this if we are not ignorning the config
+        for (alias, names) in config_map {
+            if names.contains(&author_name) || author_name == *alias {
+                authors.push(alias.to_string());
+            }
+        }
+    }
+    if authors.is_empty() && !options[0] {  // author_name is not an alias or in the config (or we ignored config)
+        authors.push(author_name);
+    }
+
+    authors
+}
+
+// remembers when each commit was made and who gets credit for it, since blame keeps coming back
+// to the same commits
+pub struct AuthorCache<'a> {
+    repo: &'a Repository,
+    config_map: &'a HashMap<String, Vec<String>>,
+    options: &'a [bool],
+    regex: &'a Regex,
+    commits: HashMap<Oid, (i64, Vec<String>)>,
+}
+
+impl<'a> AuthorCache<'a> {
+    pub fn new(repo: &'a Repository, config_map: &'a HashMap<String, Vec<String>>, options: &'a [bool], regex: &'a Regex) -> Self {
+        AuthorCache { repo, config_map, options, regex, commits: HashMap::new() }
+    }
+
+    // (commit time, authors) for a commit
+    pub fn get(&mut self, id: Oid) -> Result<&(i64, Vec<String>), Error> {
+        match self.commits.entry(id) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let commit = self.repo.find_commit(id)?;
+                let authors = get_authors(&commit, self.config_map, self.options, self.regex);
+                Ok(entry.insert((commit.time().seconds(), authors)))
+            },
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::authors::AuthorCache;
+use crate::filters::{is_ignored, is_binary_attr};
+use crate::stats::DiffSettings;
+
+use git2::{Repository, Commit, Tree, Diff, Patch, Delta, BlameOptions, ObjectType, TreeWalkMode, TreeWalkResult, Oid, Error};
+use std::collections::HashMap;
+use std::path::Path;
+
+// blames every counted file at rev and adds up how many lines of each file each author/alias was the
+// last to change (file -> (author -> lines))
+pub fn file_owners(repo: &Repository, rev: &str, settings: &DiffSettings, authors: &mut AuthorCache) -> Result<HashMap<String, HashMap<String, usize>>, Error> {
+    let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+    let mut owners: HashMap<String, HashMap<String, usize>> = HashMap::new();
+
+    for path in tree_files(repo, &commit.tree()?, settings)? {
+        let blame = repo.blame_file(Path::new(&path), Some(BlameOptions::new().newest_commit(commit.id())))?;
+        let file_owners = owners.entry(path).or_default();
+        for hunk in blame.iter() {
+            for author in &authors.get(hunk.final_commit_id())?.1 {
+                *file_owners.entry(author.clone()).or_insert(0) += hunk.lines_in_hunk();
+            }
+        }
+    }
+
+    Ok(owners)
+}
+
+// finds which commit last changed each line that a commit deletes (or modifies), by blaming the old
+// version of each file at the parent commit. returns how many of the deleted lines came from each commit
+pub fn deleted_line_origins(repo: &Repository, diff: &Diff, parent: &Commit, settings: &DiffSettings) -> Result<HashMap<Oid, usize>, Error> {
+    let mut origins: HashMap<Oid, usize> = HashMap::new();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let path = match delta.old_file().path() {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => continue,
+        };
+        if delta.status() == Delta::Added || is_ignored(repo, &path, &settings.ignore) || is_binary_attr(repo, &path) {
+            continue;
+        }
+        let patch = match Patch::from_diff(diff, idx)? {
+            Some(patch) if !patch.delta().flags().is_binary() => patch,
+            _ => continue,
+        };
+
+        let mut deleted = vec![];  // line numbers in the old file
+        for hunk in 0..patch.num_hunks() {
+            for line in 0..patch.num_lines_in_hunk(hunk)? {
+                let line = patch.line_in_hunk(hunk, line)?;
+                if let (Some(lineno), '-') = (line.old_lineno(), line.origin()) {
+                    deleted.push(lineno as usize);
+                }
+            }
+        }
+        if deleted.is_empty() {
+            continue;
+        }
+
+        let blame = repo.blame_file(Path::new(&path), Some(BlameOptions::new().newest_commit(parent.id())))?;
+        for lineno in deleted {
+            if let Some(hunk) = blame.get_line(lineno) {
+                *origins.entry(hunk.final_commit_id()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    Ok(origins)
+}
+
+// paths of every file in a tree that would have its lines counted (not ignored and not binary)
+fn tree_files(repo: &Repository, tree: &Tree, settings: &DiffSettings) -> Result<Vec<String>, Error> {
+    let mut files = vec![];
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            let path = format!("{}{}", dir, entry.name().unwrap_or(""));
+            let text = repo.find_blob(entry.id()).map(|blob| !blob.is_binary()).unwrap_or(false);
+            if text && !is_ignored(repo, &path, &settings.ignore) && !is_binary_attr(repo, &path) {
+                files.push(path);
+            }
+        }
+        TreeWalkResult::Ok
+    })?;
+
+    Ok(files)
+}
@@ -0,0 +1,135 @@
+use crate::consts::{DOCS, CONFIG_FILES, LANGUAGES};
+use crate::filters::matches_pattern;
+use crate::stats::CommitStats;
+
+use colored::Colorize;
+use std::collections::HashMap;
+
+// commits and lines an author has made to one file/directory/language etc.
+#[derive(Default)]
+pub struct Contribution {
+    pub commits: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+// author -> (file/directory/language etc. -> contribution)
+pub type Breakdown = HashMap<String, HashMap<String, Contribution>>;
+
+// groups a path by file (depth None) or by directory down to depth levels (files in shallower
+// directories are grouped by the directory they are in, or './' for the repo root)
+pub fn path_key(path: &str, depth: Option<usize>) -> String {
+    match depth {
+        None => path.to_string(),
+        Some(depth) => {
+            let dirs: Vec<&str> = path.split('/').collect();
+            let dirs = &dirs[..dirs.len() - 1];  // drop the file name
+            if dirs.is_empty() {
+                "./".to_string()
+            } else {
+                dirs[..dirs.len().min(depth)].join("/") + "/"
+            }
+        }
+    }
+}
+
+// sorts a file into a language (or 'tests', 'docs' or 'config'). overrides are 'pattern=Language' pairs
+// from the config which are checked first, then tests, docs and config files, then the file extension
+pub fn classify(path: &str, overrides: &[String], tests: &[String]) -> String {
+    for o in overrides {
+        if let Some((pattern, language)) = o.split_once('=') {
+            if matches_pattern(path, pattern) {
+                return language.to_string();
+            }
+        }
+    }
+    if tests.iter().any(|t| matches_pattern(path, t)) {
+        return "tests".to_string();
+    } else if DOCS.iter().any(|d| matches_pattern(path, d)) {
+        return "docs".to_string();
+    } else if CONFIG_FILES.iter().any(|c| matches_pattern(path, c)) {
+        return "config".to_string();
+    }
+
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => {
+            let ext = ext.to_lowercase();
+            match LANGUAGES.iter().find(|(e, _)| *e == ext) {
+                Some((_, language)) => language.to_string(),
+                None => ext,  // unknown extensions are still worth telling apart
+            }
+        },
+        _ => "other".to_string(),
+    }
+}
+
+// adds each file in a commit to the breakdown under whatever key_fn groups it by - the commit only
+// counts once for each key, however many of its files share it
+pub fn add_commit_breakdown(breakdown: &mut Breakdown, author: &str, stats: &CommitStats, key_fn: impl Fn(&str) -> String) {
+    let entries = breakdown.entry(author.to_string()).or_default();
+    let mut seen: Vec<String> = vec![];
+    for file in &stats.files {
+        let key = key_fn(&file.path);
+        let entry = entries.entry(key.clone()).or_default();
+        entry.insertions += file.insertions;
+        entry.deletions += file.deletions;
+        if !seen.contains(&key) {
+            entry.commits += 1;
+            seen.push(key);
+        }
+    }
+}
+
+// prints a table for each author with their contributions to each key, biggest first
+pub fn print_breakdown(breakdown: &Breakdown, key_header: &str) {
+    let width = 20 + 3 + 40 + 3 + 10 + 3 + 15 + 3 + 15 + 1;
+    println!();
+    println!("{:-<width$}", "");
+    println!("{0: <20} | {1: <40} | {2: <10} | {3: <15} | {4: <15}",
+        "author".yellow(), key_header.yellow(), "commits".yellow(), "lines added".yellow(), "lines deleted".yellow());
+    println!("{:-<width$}", "");
+
+    let mut authors: Vec<&String> = breakdown.keys().collect();
+    authors.sort();
+    for author in authors {
+        let mut entries: Vec<(&String, &Contribution)> = breakdown[author].iter().collect();
+        entries.sort_by(|a, b| (b.1.insertions + b.1.deletions).cmp(&(a.1.insertions + a.1.deletions)).then(a.0.cmp(b.0)));
+        for (count, (key, data)) in entries.iter().enumerate() {
+            let name = if count == 0 { author.as_str() } else { "" };  // only name the author on their first row
+            println!("{0: <20} | {1: <40} | {2: <10} | {3: <15} | {4: <15}", name, key, data.commits, data.insertions, data.deletions);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::DEFAULT_TESTS;
+
+    #[test]
+    fn paths_are_cut_to_a_directory_depth() {
+        assert_eq!(path_key("src/ui/view.rs", None), "src/ui/view.rs");
+        assert_eq!(path_key("src/ui/view.rs", Some(1)), "src/");
+        assert_eq!(path_key("src/ui/view.rs", Some(2)), "src/ui/");
+        assert_eq!(path_key("src/ui/view.rs", Some(5)), "src/ui/");
+        assert_eq!(path_key("main.rs", Some(1)), "./");
+    }
+
+    #[test]
+    fn files_are_classified_by_overrides_then_kind_then_extension() {
+        let tests: Vec<String> = DEFAULT_TESTS.iter().map(|t| t.to_string()).collect();
+        let overrides = vec!["*.h=C++".to_string(), "docs/*.rs=Rust".to_string()];
+        assert_eq!(classify("include/util.h", &overrides, &tests), "C++");
+        assert_eq!(classify("docs/example.rs", &overrides, &tests), "Rust");
+        assert_eq!(classify("tests/cli.rs", &overrides, &tests), "tests");
+        assert_eq!(classify("src/parser_test.go", &overrides, &tests), "tests");
+        assert_eq!(classify("docs/guide.html", &overrides, &tests), "docs");
+        assert_eq!(classify("README.md", &overrides, &tests), "docs");
+        assert_eq!(classify("Cargo.toml", &overrides, &tests), "config");
+        assert_eq!(classify("src/Main.RS", &overrides, &tests), "Rust");
+        assert_eq!(classify("src/data.xyz", &overrides, &tests), "xyz");
+        assert_eq!(classify(".bashrc", &overrides, &tests), "other");
+        assert_eq!(classify("bin/run", &overrides, &tests), "other");
+    }
+}
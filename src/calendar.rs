@@ -0,0 +1,220 @@
+use crate::timeline::{ActivityLog, local_time};
+
+use chrono::{Datelike, Days, NaiveDate};
+use colored::Colorize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+// the same shades of green GitHub uses, from no commits to the busiest days
+const SHADES: [(u8, u8, u8); 5] = [(235, 237, 240), (155, 233, 168), (64, 196, 99), (48, 161, 78), (33, 110, 57)];
+const CELL: usize = 13;  // pixels per day in the svg (including the gap)
+
+// a year of daily commit counts, one row per weekday (monday first) and one column per week
+struct Calendar {
+    name: String,
+    start: NaiveDate,  // the monday of the first column
+    first: NaiveDate,  // a year before the end, so the first column is usually only part of a week
+    end: NaiveDate,
+    days: HashMap<NaiveDate, usize>,
+}
+
+impl Calendar {
+    fn count(&self, date: NaiveDate) -> usize {
+        self.days.get(&date).copied().unwrap_or(0)
+    }
+
+    fn weeks(&self) -> usize {
+        ((self.end - self.start).num_days() / 7 + 1) as usize
+    }
+
+    fn date(&self, week: usize, weekday: usize) -> NaiveDate {
+        self.start + Days::new((week * 7 + weekday) as u64)
+    }
+
+    fn shown(&self, date: NaiveDate) -> bool {
+        date >= self.first && date <= self.end
+    }
+
+    // 0 for no commits, then 1-4 in quarters of the busiest day
+    fn level(&self, count: usize) -> usize {
+        let max = self.days.values().max().copied().unwrap_or(0).max(1);
+        (count * 4).div_ceil(max).min(4)
+    }
+}
+
+// builds a calendar for each author (or one for everyone) covering the year up to the last commit
+fn calendars(log: &ActivityLog, team: bool) -> Vec<Calendar> {
+    let end = match log.values().flatten().map(|a| local_time(a).date_naive()).max() {
+        Some(end) => end,
+        None => return vec![],
+    };
+    let first = end - Days::new(364);
+    let start = first - Days::new(first.weekday().num_days_from_monday() as u64);
+
+    let mut authors: Vec<&String> = log.keys().collect();
+    authors.sort();
+    let groups: Vec<(String, Vec<&String>)> = if team {
+        vec![("team".to_string(), authors)]
+    } else {
+        authors.into_iter().map(|a| (a.clone(), vec![a])).collect()
+    };
+
+    groups.into_iter()
+        .map(|(name, members)| {
+            let mut days: HashMap<NaiveDate, usize> = HashMap::new();
+            let mut seen = HashSet::new();  // commits with several authors only count once for the team
+            for activity in members.iter().flat_map(|m| &log[*m]) {
+                let date = local_time(activity).date_naive();
+                if date >= first && seen.insert(activity.commit) {
+                    *days.entry(date).or_insert(0) += 1;
+                }
+            }
+            Calendar { name, start, first, end, days }
+        })
+        .collect()
+}
+
+// prints a github-style heatmap of commits per day for each author (or the whole team) over the last year
+pub fn print_calendar(log: &ActivityLog, team: bool) {
+    for calendar in calendars(log, team) {
+        let weeks = calendar.weeks();
+        println!();
+        println!("{} - {} commits in the year to {}", calendar.name.yellow(), calendar.days.values().sum::<usize>(), calendar.end);
+
+        // month names above the first week of each month
+        let mut header = " ".repeat(4);
+        let mut week = 0;
+        while week < weeks {
+            let date = calendar.date(week, 0);
+            if week == 0 || date.month() != calendar.date(week - 1, 0).month() {
+                header.push_str(&format!("{: <6}", date.format("%b")));
+                week += 3;
+            } else {
+                header.push_str("  ");
+                week += 1;
+            }
+        }
+        println!("{}", header);
+
+        for weekday in 0..7 {
+            let label = match weekday { 0 => "Mon", 2 => "Wed", 4 => "Fri", _ => "" };
+            print!("{: <4}", label);
+            for week in 0..weeks {
+                let date = calendar.date(week, weekday);
+                if !calendar.shown(date) {
+                    print!("  ");
+                    continue;
+                }
+                match calendar.level(calendar.count(date)) {
+                    0 => print!("{} ", "■".bright_black()),
+                    level => {
+                        let (r, g, b) = SHADES[level];
+                        print!("{} ", "■".truecolor(r, g, b));
+                    },
+                }
+            }
+            println!();
+        }
+
+        let legend: Vec<String> = (1..5).map(|l| "■".truecolor(SHADES[l].0, SHADES[l].1, SHADES[l].2).to_string()).collect();
+        println!("{: <4}less {} {} more", "", "■".bright_black(), legend.join(" "));
+    }
+}
+
+// writes the same heatmaps to an svg file, one under the other
+pub fn write_calendar_svg(log: &ActivityLog, team: bool, path: &str) {
+    let calendars = calendars(log, team);
+    let block = 20 + 15 + 7 * CELL + 10;  // name, month names, days and a gap
+    let width = 30 + calendars.first().map(|c| c.weeks()).unwrap_or(0) * CELL;
+    let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"10\">\n",
+        width, block * calendars.len());
+
+    for (i, calendar) in calendars.iter().enumerate() {
+        let top = i * block;
+        svg.push_str(&format!("  <text x=\"0\" y=\"{}\" font-size=\"12\">{} - {} commits</text>\n",
+            top + 12, escape(&calendar.name), calendar.days.values().sum::<usize>()));
+        for week in 0..calendar.weeks() {
+            let monday = calendar.date(week, 0);
+            if week == 0 || monday.month() != calendar.date(week - 1, 0).month() {
+                svg.push_str(&format!("  <text x=\"{}\" y=\"{}\">{}</text>\n", 30 + week * CELL, top + 30, monday.format("%b")));
+            }
+            for weekday in 0..7 {
+                let date = calendar.date(week, weekday);
+                if !calendar.shown(date) {
+                    continue;
+                }
+                let count = calendar.count(date);
+                let (r, g, b) = SHADES[calendar.level(count)];
+                svg.push_str(&format!("  <rect x=\"{}\" y=\"{}\" width=\"11\" height=\"11\" rx=\"2\" fill=\"#{:02x}{:02x}{:02x}\"><title>{}: {} commits</title></rect>\n",
+                    30 + week * CELL, top + 35 + weekday * CELL, r, g, b, date, count));
+            }
+        }
+        for (weekday, label) in [(0, "Mon"), (2, "Wed"), (4, "Fri")] {
+            svg.push_str(&format!("  <text x=\"0\" y=\"{}\">{}</text>\n", top + 44 + weekday * CELL, label));
+        }
+    }
+    svg.push_str("</svg>\n");
+
+    match fs::write(path, svg) {
+        Ok(()) => println!("Wrote calendar to {}", path),
+        Err(e) => println!("Couldn't write {}: {}", path, e),
+    }
+}
+
+// escapes the characters that would break the svg
+fn escape(name: &str) -> String {
+    name.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timeline::Activity;
+    use git2::Oid;
+
+    fn activity(commit: u8, day: u32) -> Activity {
+        let time = NaiveDate::from_ymd_opt(2026, 10, day).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc().timestamp();
+        Activity { commit: Oid::from_bytes(&[commit; 20]).unwrap(), time, offset: 0, lines: 1 }
+    }
+
+    #[test]
+    fn weeks_start_on_the_monday_before_a_year_ago() {
+        let log: ActivityLog = HashMap::from([("alice".to_string(), vec![activity(1, 15)])]);  // a thursday
+        let calendar = &calendars(&log, false)[0];
+        assert_eq!(calendar.end, NaiveDate::from_ymd_opt(2026, 10, 15).unwrap());
+        assert_eq!(calendar.first, NaiveDate::from_ymd_opt(2025, 10, 16).unwrap());
+        assert_eq!(calendar.start, NaiveDate::from_ymd_opt(2025, 10, 13).unwrap());
+        assert_eq!(calendar.start.weekday(), chrono::Weekday::Mon);
+        assert_eq!(calendar.weeks(), 53);
+
+        assert!(!calendar.shown(calendar.start));
+        assert!(calendar.shown(calendar.first));
+        assert!(calendar.shown(calendar.end));
+        assert!(!calendar.shown(calendar.end + Days::new(1)));
+    }
+
+    #[test]
+    fn levels_are_quarters_of_the_busiest_day() {
+        let log: ActivityLog = HashMap::from([("alice".to_string(), (0..8).map(|c| activity(c, 15)).collect())]);
+        let calendar = &calendars(&log, false)[0];
+        let levels: Vec<usize> = [0, 1, 2, 3, 4, 5, 6, 7, 8].iter().map(|&c| calendar.level(c)).collect();
+        assert_eq!(levels, [0, 1, 1, 2, 2, 3, 3, 4, 4]);
+
+        let empty = Calendar { days: HashMap::new(), ..calendars(&log, false).remove(0) };
+        assert_eq!(empty.level(0), 0);
+    }
+
+    #[test]
+    fn team_calendar_counts_shared_commits_once() {
+        let log: ActivityLog = HashMap::from([
+            ("alice".to_string(), vec![activity(1, 14), activity(2, 15)]),
+            ("bob".to_string(), vec![activity(2, 15), activity(3, 15)]),  // commit 2 credits both of them
+        ]);
+        let team = &calendars(&log, true)[0];
+        assert_eq!(team.count(NaiveDate::from_ymd_opt(2026, 10, 15).unwrap()), 2);
+        assert_eq!(team.days.values().sum::<usize>(), 3);
+
+        let per_author: Vec<usize> = calendars(&log, false).iter().map(|c| c.days.values().sum()).collect();
+        assert_eq!(per_author, [2, 2]);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::authors::get_authors;
+use crate::consts::CHANGELOG_TYPES;
+use crate::releases::find_releases;
+
+use git2::{Repository, Oid, Error};
+use regex::Regex;
+use std::collections::HashMap;
+
+// the parts of a conventional commit message, eg. 'feat(ui)!: add dark mode'
+struct Change {
+    kind: String,
+    scope: Option<String>,
+    description: String,
+    breaking: Option<String>,  // the BREAKING CHANGE footer (or the description if only marked with !)
+    id: Oid,
+    authors: Vec<String>,
+}
+
+// 'type(scope)!: description' - the type is checked by parse_change
+fn header_regex() -> Regex {
+    Regex::new(r"^([A-Za-z]+)(?:\(([^)]*)\))?(!)?:\s*(.+)$").unwrap()
+}
+
+// parses the first line of a commit message as 'type(scope)!: description' (after any [author] tags used
+// for autogenerated aliases) and looks for a BREAKING CHANGE footer in the rest. Types aren't case sensitive,
+// but ones that aren't in CHANGELOG_TYPES have to be lowercase so things like 'WIP:' or 'Merge:' aren't
+// mistaken for one
+fn parse_change(message: &str, header: &Regex) -> Option<(String, Option<String>, String, Option<String>)> {
+    let (first, rest) = message.split_once('\n').unwrap_or((message, ""));
+    let first = first.trim();
+    let first = match first.strip_prefix('[').and_then(|f| f.split_once(']')) {
+        Some((_, after)) => after.trim_start(),
+        None => first,
+    };
+    let captures = header.captures(first)?;
+    let kind = captures[1].to_lowercase();
+    if kind != captures[1] && !CHANGELOG_TYPES.iter().any(|(k, _)| *k == kind) {
+        return None;
+    }
+
+    let description = captures[4].trim().to_string();
+    let breaking = match (breaking_footer(rest), captures.get(3)) {
+        (Some(footer), _) => Some(footer),
+        (None, Some(_)) => Some(description.clone()),
+        (None, None) => None,
+    };
+
+    Some((kind, captures.get(2).map(|s| s.as_str().to_string()), description, breaking))
+}
+
+// the text of a BREAKING CHANGE footer, including any lines it carries on over until a blank line or the
+// next footer (eg. 'Refs: #123' or 'Reviewed-by: someone')
+fn breaking_footer(body: &str) -> Option<String> {
+    let token = Regex::new(r"^(?:BREAKING CHANGE|[\w-]+)(?:: | #)").unwrap();
+    let mut lines = body.lines().skip_while(|l| !l.starts_with("BREAKING CHANGE:") && !l.starts_with("BREAKING-CHANGE:"));
+    let first = lines.next()?;
+    let mut footer = vec![first["BREAKING CHANGE:".len()..].trim()];
+    footer.extend(lines.take_while(|l| !l.trim().is_empty() && !token.is_match(l)).map(|l| l.trim()));
+
+    Some(footer.join(" "))
+}
+
+// the newest tag (in version order) that is behind the given commit, to start the changelog from
+fn previous_release(repo: &Repository, to: Oid) -> Result<Option<(String, Oid)>, Error> {
+    let (releases, _) = find_releases(repo)?;
+    for release in releases.iter().rev() {
+        if release.commit != to && repo.graph_descendant_of(to, release.commit)? {
+            return Ok(Some((release.name.clone(), release.commit)));
+        }
+    }
+
+    Ok(None)
+}
+
+// prints a markdown changelog of the commits after from (the last tag if not given) up to to (HEAD if not given),
+// grouped by conventional commit type with breaking changes first, crediting each change's authors/aliases
+pub fn print_changelog(repo: &Repository, from: Option<&String>, to: Option<&String>,
+                       config_map: &HashMap<String, Vec<String>>, options: &[bool], regex: &Regex) -> Result<(), Error> {
+    let to_name = to.map(|t| t.as_str()).unwrap_or("HEAD");
+    let to = repo.revparse_single(to_name)?.peel_to_commit()?.id();
+    let from = match from {
+        Some(from) => Some((from.clone(), repo.revparse_single(from)?.peel_to_commit()?.id())),
+        None => previous_release(repo, to)?,
+    };
+
+    let mut rw = repo.revwalk()?;
+    rw.push(to)?;
+    if let Some((_, id)) = &from {
+        rw.hide(*id)?;
+    }
+
+    let header = header_regex();
+    let mut changes: Vec<Change> = vec![];
+    let mut other: Vec<(String, Oid, Vec<String>)> = vec![];  // commits that aren't conventional
+    for id in rw.filter_map(|x| x.ok()) {
+        let commit = repo.find_commit(id)?;
+        if commit.parent_count() > 1 {  // merges just repeat the changes they bring in
+            continue;
+        }
+        let authors = get_authors(&commit, config_map, options, regex);
+        match parse_change(commit.message().unwrap_or(""), &header) {
+            Some((kind, scope, description, breaking)) => changes.push(Change { kind, scope, description, breaking, id, authors }),
+            None => other.push((commit.summary().unwrap_or("").to_string(), id, authors)),
+        }
+    }
+    changes.reverse();  // oldest first reads more like a story
+    other.reverse();
+
+    let entry = |scope: &Option<String>, description: &str, id: &Oid, authors: &[String]| {
+        let scope = scope.as_ref().map(|s| format!("**{}:** ", s)).unwrap_or_default();
+        format!("- {}{} ({}) - {}", scope, description, &id.to_string()[..7], authors.join(", "))
+    };
+
+    match &from {
+        Some((name, _)) => println!("# Changes from {} to {}", name, to_name),
+        None => println!("# Changes up to {}", to_name),
+    }
+
+    let breaking: Vec<&Change> = changes.iter().filter(|c| c.breaking.is_some()).collect();
+    if !breaking.is_empty() {
+        println!();
+        println!("## Breaking changes");
+        println!();
+        for change in breaking {
+            println!("{}", entry(&change.scope, change.breaking.as_deref().unwrap_or(""), &change.id, &change.authors));
+        }
+    }
+
+    // known types in the usual order, then any others alphabetically
+    let mut kinds: Vec<String> = CHANGELOG_TYPES.iter().map(|(k, _)| k.to_string()).collect();
+    let mut extra: Vec<String> = changes.iter().map(|c| c.kind.clone()).filter(|k| !kinds.contains(k)).collect();
+    extra.sort();
+    extra.dedup();
+    kinds.extend(extra);
+    for kind in kinds {
+        let of_kind: Vec<&Change> = changes.iter().filter(|c| c.kind == kind).collect();
+        if of_kind.is_empty() {
+            continue;
+        }
+        let title = match CHANGELOG_TYPES.iter().find(|(k, _)| *k == kind) {
+            Some((_, title)) => title.to_string(),
+            None => kind.clone(),
+        };
+        println!();
+        println!("## {}", title);
+        println!();
+        for change in of_kind {
+            println!("{}", entry(&change.scope, &change.description, &change.id, &change.authors));
+        }
+    }
+
+    if !other.is_empty() {
+        println!();
+        println!("## Other changes");
+        println!();
+        for (summary, id, authors) in &other {
+            println!("{}", entry(&None, summary, id, authors));
+        }
+    }
+
+    // everyone who worked on this, most changes first
+    let mut contributors: HashMap<&String, usize> = HashMap::new();
+    for authors in changes.iter().map(|c| &c.authors).chain(other.iter().map(|o| &o.2)) {
+        for author in authors {
+            *contributors.entry(author).or_insert(0) += 1;
+        }
+    }
+    let mut contributors: Vec<(&String, usize)> = contributors.into_iter().collect();
+    contributors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    if !contributors.is_empty() {
+        println!();
+        println!("## Contributors");
+        println!();
+        for (author, count) in contributors {
+            println!("- {} ({} {})", author, count, if count == 1 { "change" } else { "changes" });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(message: &str) -> Option<(String, Option<String>, String, Option<String>)> {
+        parse_change(message, &header_regex())
+    }
+
+    #[test]
+    fn parses_type_scope_and_breaking_marker() {
+        assert_eq!(parse("feat(ui)!: dark mode"), Some(("feat".to_string(), Some("ui".to_string()), "dark mode".to_string(), Some("dark mode".to_string()))));
+        assert_eq!(parse("fix: crash\n\nmore detail"), Some(("fix".to_string(), None, "crash".to_string(), None)));
+        assert_eq!(parse("[alice, bob] docs(readme): typo").map(|c| c.0), Some("docs".to_string()));
+    }
+
+    #[test]
+    fn known_types_are_case_insensitive() {
+        assert_eq!(parse("Feat: dark mode").map(|c| c.0), Some("feat".to_string()));
+        assert_eq!(parse("FIX(ui): crash").map(|c| c.0), Some("fix".to_string()));
+        assert_eq!(parse("wip: custom type").map(|c| c.0), Some("wip".to_string()));
+    }
+
+    #[test]
+    fn ignores_other_capitalised_prefixes() {
+        assert_eq!(parse("WIP: half done"), None);
+        assert_eq!(parse("README: update"), None);
+        assert_eq!(parse("Merge: branch"), None);
+        assert_eq!(parse("no type at all"), None);
+    }
+
+    #[test]
+    fn joins_multi_line_breaking_footer() {
+        let message = "feat: new api\n\nbody\n\nBREAKING CHANGE: the old api\nis gone now\nRefs: #12\n";
+        assert_eq!(parse(message).and_then(|c| c.3), Some("the old api is gone now".to_string()));
+        let message = "fix: x\n\nBREAKING-CHANGE: config moved\n\nunrelated paragraph";
+        assert_eq!(parse(message).and_then(|c| c.3), Some("config moved".to_string()));
+    }
+}
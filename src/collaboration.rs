@@ -0,0 +1,63 @@
+use colored::Colorize;
+use std::collections::HashMap;
+
+// modifier -> (original author -> lines the modifier changed/deleted that the original author wrote)
+pub type Matrix = HashMap<String, HashMap<String, usize>>;
+
+pub fn print_matrix(matrix: &Matrix, format: &str) {
+    let mut authors: Vec<&String> = matrix.keys().chain(matrix.values().flat_map(|row| row.keys())).collect();
+    authors.sort();
+    authors.dedup();
+    let lines = |modifier: &String, original: &String| matrix.get(modifier).and_then(|row| row.get(original)).copied().unwrap_or(0);
+
+    match format {
+        "dot" => {  // graphviz, with an edge from whoever changed the code to whoever wrote it
+            println!("digraph collaboration {{");
+            for modifier in &authors {
+                for original in &authors {
+                    let count = lines(modifier, original);
+                    if modifier != original && count > 0 {  // editing your own code isn't collaboration
+                        println!("    \"{}\" -> \"{}\" [label=\"{}\"];", escape(modifier), escape(original), count);
+                    }
+                }
+            }
+            println!("}}");
+        },
+        "json" => {
+            let rows: Vec<String> = authors.iter()
+                .map(|modifier| {
+                    let cells: Vec<String> = authors.iter()
+                        .filter(|original| lines(modifier, original) > 0)
+                        .map(|original| format!("\"{}\": {}", escape(original), lines(modifier, original)))
+                        .collect();
+                    format!("  \"{}\": {{{}}}", escape(modifier), cells.join(", "))
+                })
+                .collect();
+            println!("{{\n{}\n}}", rows.join(",\n"));
+        },
+        _ => {  // table with a row for whoever changed the code and a column for whoever wrote it
+            let width = authors.iter().map(|a| a.len()).max().unwrap_or(0).max(10);
+            println!();
+            println!("{}", "lines changed by (rows) that were written by (columns)".yellow());
+            println!("{:-<w$}", "", w = 20 + (width + 3) * authors.len() + 1);
+            print!("{: <20}", "");
+            for original in &authors {
+                print!(" | {: <width$}", original.yellow());
+            }
+            println!();
+            println!("{:-<w$}", "", w = 20 + (width + 3) * authors.len() + 1);
+            for modifier in &authors {
+                print!("{: <20}", modifier);
+                for original in &authors {
+                    print!(" | {: <width$}", lines(modifier, original));
+                }
+                println!();
+            }
+        },
+    }
+}
+
+// escapes quotes and backslashes for dot/json strings
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
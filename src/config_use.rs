@@ -1,4 +1,4 @@
-use crate::consts::CONFIG;
+use crate::consts::{CONFIG, SETTING};
 
 use std::env;
 use std::collections::HashMap;
@@ -10,9 +10,9 @@ pub fn add_alias(names: &mut Vec<String>) -> Result<(), io::Error> {
     let config_path = get_config_path();
     let alias = names.remove(0);          // get the first name in the list of args
     match get_names_with_alias(&alias) {  // if it is already in the config, new entries to existing ones
-        Ok(result) if result.len() > 0 => { 
-            *names = vec![result, names.to_vec()].concat();
-            let _ = delete_alias(&mut vec![alias.clone()], true)?;
+        Ok(result) if !result.is_empty() => { 
+            *names = [result, names.to_vec()].concat();
+            delete_alias(std::slice::from_ref(&alias), true)?;
         },
         _ => {}
     }
@@ -35,12 +35,13 @@ pub fn add_alias(names: &mut Vec<String>) -> Result<(), io::Error> {
 }
 
 // deletes alias entries from config
-pub fn delete_alias(aliases: &mut Vec<String>, quietly: bool) -> Result<(), io::Error> {
+pub fn delete_alias(aliases: &[String], quietly: bool) -> Result<(), io::Error> {
     let config_path = get_config_path();
     let config_file = OpenOptions::new()
         .read(true)
         .write(true)
         .create(true)
+        .truncate(false)
         .open(&config_path)?;
 
     let lines = BufReader::new(config_file).lines()
@@ -64,6 +65,25 @@ pub fn delete_alias(aliases: &mut Vec<String>, quietly: bool) -> Result<(), io::
     Ok(())
 }
 
+// adds a setting in the form '$key: value1, value2, ... \n', appending to any values already set
+pub fn add_setting(args: &mut Vec<String>) -> Result<(), io::Error> {
+    if args.len() < 2 {
+        println!("Provide a setting name followed by at least one value");
+        return Ok(());
+    }
+    if let Some(bad) = args.iter().skip(1).find(|a| a.contains(", ")) {  // values are stored separated by ', '
+        println!("Setting values can't contain ', ' (in '{}')", bad);
+        return Ok(());
+    }
+    args[0] = format!("{}{}", SETTING, args[0]);  // settings are stored like aliases, just with a prefix
+    add_alias(args)
+}
+
+// get the values of a setting, or an empty list if it has not been set
+pub fn get_setting(key: &str) -> Result<Vec<String>, io::Error> {
+    get_names_with_alias(&format!("{}{}", SETTING, key))
+}
+
 // deletes the config file (resetting it)
 pub fn reset_config() -> Result<(), io::Error> {
     let config_path = get_config_path();
@@ -80,21 +100,19 @@ pub fn get_map() -> Result<HashMap<String, Vec<String>>, io::Error> {
         .read(true)
         .write(true)
         .create(true)
+        .truncate(false)
         .open(config_path)?;
 
     let mut map: HashMap<String, Vec<String>> = HashMap::new();
     for line in BufReader::new(config_file).lines() {
         let line = line?;
-        if !line.starts_with("# ") {
-            match line.split_once(':') {
-                Some((alias, ns)) => {
-                    let names = ns.trim()
-                        .split(", ")
-                        .map(|n| n.to_string())
-                        .collect();
-                    map.insert(alias.to_string(), names);
-                }
-                _ => ()
+        if !line.starts_with("# ") && !line.starts_with(SETTING) {  // skip the path and any settings
+            if let Some((alias, ns)) = line.split_once(':') {
+                let names = ns.trim()
+                    .split(", ")
+                    .map(|n| n.to_string())
+                    .collect();
+                map.insert(alias.to_string(), names);
             }
         }
     }
@@ -148,7 +166,7 @@ pub fn get_path() -> Result<String, io::Error> {
 }
 
 // save default path of repository to config
-pub fn set_path(args: &mut Vec<String>) -> Result<(), io::Error> {
+pub fn set_path(args: &[String]) -> Result<(), io::Error> {
     let config_path = get_config_path();
     match args.len() {
         0 => println!("Provide a path to add to the config"),
@@ -166,6 +184,7 @@ pub fn set_path(args: &mut Vec<String>) -> Result<(), io::Error> {
                 .read(true)
                 .write(true)
                 .create(true)
+                .truncate(false)
                 .open(&config_path)?;
 
             let mut lines = BufReader::new(config_file).lines();
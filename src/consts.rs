@@ -1,5 +1,9 @@
 pub const CONFIG: &str = "/.gsConfig";  // path of config file
 pub const UNTAGGED: &str = "untagged";  // when match not found for autogenerated aliases
+pub const UNRELEASED: &str = "unreleased";  // commits that aren't in any tag yet
+pub const SETTING: &str = "$";          // prefix for config lines that hold settings rather than aliases
+pub const BLAME_IGNORE_REVS: &str = ".git-blame-ignore-revs";  // commits to skip, shared with git blame
+pub const WAW_IGNORE_REVS: &str = ".waw-ignore-revs";          // commits to skip that git blame shouldn't
 
 // items in the arg vector when processing options
 pub const FILTERS   : usize = 0;
@@ -7,6 +11,40 @@ pub const EXCLUDE   : usize = 1;
 pub const SEARCH    : usize = 2;
 pub const CI_SEARCH : usize = 3;
 pub const BRANCHES  : usize = 4;
+pub const IGNORE    : usize = 5;
+pub const MERGES    : usize = 6;
+pub const RENAMES   : usize = 7;
+pub const WHITESPACE: usize = 8;
+pub const IGNORE_REVS: usize = 9;
+pub const SURVIVING : usize = 10;
+pub const BREAKDOWN : usize = 11;
+pub const CHURN     : usize = 12;
+pub const MATRIX    : usize = 13;
+pub const HOTSPOTS  : usize = 14;
+pub const BUS_FACTOR: usize = 15;
+pub const CODEOWNERS: usize = 16;
+pub const TIMELINE  : usize = 17;
+pub const CALENDAR  : usize = 18;
+pub const SESSIONS  : usize = 19;
+pub const DEADLINES : usize = 20;
+pub const RELEASES  : usize = 21;
+pub const CHANGELOG : usize = 22;
+
+pub const MAX_COUPLING_FILES: usize = 50;  // commits changing more files than this aren't used for change coupling
+
+// files that are (almost) never written by hand, so are left out of line counts by default
+pub const DEFAULT_IGNORE: [&str; 16] = [
+    "Cargo.lock", "package-lock.json", "yarn.lock", "pnpm-lock.yaml", "poetry.lock", "Gemfile.lock",
+    "composer.lock", "go.sum", "*.min.js", "*.min.css", "*.map", "*.snap", "__snapshots__/",
+    "vendor/", "node_modules/", "third_party/",
+];
+
+// conventional commit types and their changelog headings, in the order they're shown
+pub const CHANGELOG_TYPES: [(&str, &str); 11] = [
+    ("feat", "Features"), ("fix", "Bug fixes"), ("perf", "Performance"), ("refactor", "Refactoring"),
+    ("docs", "Documentation"), ("test", "Tests"), ("build", "Build"), ("ci", "CI"), ("style", "Style"),
+    ("chore", "Chores"), ("revert", "Reverts"),
+];
 
 // certain time variables in seconds for checking commit recency
 pub const HOURS     : i64 = 60*60;
@@ -14,3 +52,35 @@ pub const DAYS      : i64 = 24*HOURS;
 pub const WEEKS     : i64 = 7*DAYS;
 pub const MONTHS    : i64 = 30*DAYS;
 pub const YEARS     : i64 = 52*WEEKS;
+
+// path conventions for test code
+pub const DEFAULT_TESTS: [&str; 12] = [
+    "tests/", "test/", "__tests__/", "spec/", "*_test.*", "*_tests.*", "test_*.py", "*.test.*",
+    "*.spec.*", "*Test.java", "*Tests.cs", "*_spec.rb",
+];
+
+// file types that are counted as docs/config rather than by language (checked before LANGUAGES)
+pub const DOCS: [&str; 10] = ["docs/", "doc/", "*.md", "*.rst", "*.adoc", "*.txt", "README*", "LICENSE*", "CHANGELOG*", "CONTRIBUTING*"];
+pub const CONFIG_FILES: [&str; 14] = [
+    "*.toml", "*.yaml", "*.yml", "*.json", "*.ini", "*.cfg", "*.conf", "*.xml", "*.properties", ".*ignore",
+    ".gitattributes", ".editorconfig", "Dockerfile", "Makefile",
+];
+
+// file extensions of languages where ' starts a string rather than a character literal/lifetime, and of
+// ones with '#' comments rather than '//' and '/* */' (used to spot formatting-only commits)
+pub const SINGLE_QUOTE_STRINGS: [&str; 23] = [
+    "js", "mjs", "jsx", "ts", "tsx", "py", "rb", "php", "sh", "bash", "lua", "dart", "r", "pl", "sql",
+    "css", "scss", "sass", "vue", "svelte", "toml", "yaml", "yml",
+];
+pub const HASH_COMMENTS: [&str; 10] = ["py", "rb", "sh", "bash", "r", "pl", "toml", "yaml", "yml", "ex"];
+
+// file extension -> language
+pub const LANGUAGES: [(&str, &str); 40] = [
+    ("rs", "Rust"), ("py", "Python"), ("js", "JavaScript"), ("mjs", "JavaScript"), ("jsx", "JavaScript"),
+    ("ts", "TypeScript"), ("tsx", "TypeScript"), ("java", "Java"), ("kt", "Kotlin"), ("scala", "Scala"),
+    ("c", "C"), ("h", "C"), ("cpp", "C++"), ("cc", "C++"), ("hpp", "C++"), ("cs", "C#"), ("go", "Go"),
+    ("rb", "Ruby"), ("php", "PHP"), ("swift", "Swift"), ("m", "Objective-C"), ("hs", "Haskell"),
+    ("ml", "OCaml"), ("ex", "Elixir"), ("erl", "Erlang"), ("clj", "Clojure"), ("lua", "Lua"), ("r", "R"),
+    ("sh", "Shell"), ("bash", "Shell"), ("sql", "SQL"), ("html", "HTML"), ("css", "CSS"), ("scss", "CSS"),
+    ("sass", "CSS"), ("vue", "Vue"), ("svelte", "Svelte"), ("dart", "Dart"), ("pl", "Perl"), ("tex", "TeX"),
+];
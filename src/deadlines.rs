@@ -0,0 +1,106 @@
+use crate::consts::HOURS;
+use crate::timeline::ActivityLog;
+
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use colored::Colorize;
+
+// a named deadline from the config, like 'Sprint 2 due 2026-10-20 17:00'
+pub struct Milestone {
+    pub name: String,
+    pub due: i64,
+}
+
+// reads a 'YYYY-MM-DD HH:MM' (or just 'YYYY-MM-DD', meaning the start or end of that day) time in the local timezone
+pub fn parse_time(text: &str, end_of_day: bool) -> Option<i64> {
+    let time = match NaiveDateTime::parse_from_str(text.trim(), "%Y-%m-%d %H:%M") {
+        Ok(time) => time,
+        Err(_) if end_of_day => NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").ok()?.and_hms_opt(23, 59, 59)?,
+        Err(_) => NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0)?,
+    };
+    Local.from_local_datetime(&time).earliest().map(|t| t.timestamp())
+}
+
+// turns 'name due time' settings into milestones in order of their deadlines
+pub fn parse_milestones(settings: &[String]) -> Vec<Milestone> {
+    let mut milestones: Vec<Milestone> = vec![];
+    for setting in settings {
+        match setting.rsplit_once(" due ").and_then(|(name, due)| parse_time(due, true).map(|due| (name, due))) {
+            Some((name, due)) => milestones.push(Milestone { name: name.trim().to_string(), due }),
+            None => println!("Couldn't read milestone '{}' (expected 'name due YYYY-MM-DD HH:MM')", setting),
+        }
+    }
+    milestones.sort_by_key(|m| m.due);
+
+    milestones
+}
+
+// for each milestone, prints what share of each author's commits and lines for it were made in the last
+// 48 and 24 hours before the deadline, and how much came in up to late_hours after it. a milestone's work
+// starts at the previous deadline, so late work for one milestone also counts towards the next
+pub fn print_deadlines(log: &ActivityLog, milestones: &[Milestone], late_hours: i64) {
+    let mut authors: Vec<&String> = log.keys().collect();
+    authors.sort();
+
+    for (i, milestone) in milestones.iter().enumerate() {
+        let start = if i == 0 { i64::MIN } else { milestones[i - 1].due };
+        let due = Local.timestamp_opt(milestone.due, 0).single().map(|t| t.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default();
+
+        let width = 20 + (3 + 10) * 2 + (3 + 15) * 3 + 1;
+        println!();
+        println!("{} (due {})", milestone.name.yellow(), due);
+        println!("{:-<width$}", "");
+        println!("{0: <20} | {1: <10} | {2: <10} | {3: <15} | {4: <15} | {5: <15}", "author".yellow(), "commits".yellow(),
+            "lines".yellow(), "final 48h".yellow(), "final 24h".yellow(), format!("late ({}h)", late_hours).yellow());
+        println!("{0: <20} | {1: <10} | {2: <10} | {3: <15} | {3: <15} | {3: <15}", "", "", "", "commits / lines");
+        println!("{:-<width$}", "");
+        for author in &authors {
+            let activities: Vec<_> = log[*author].iter()
+                .filter(|a| a.time > start && a.time <= milestone.due + late_hours * HOURS)
+                .collect();
+            if activities.is_empty() {
+                continue;
+            }
+            let commits = activities.len();
+            let lines: usize = activities.iter().map(|a| a.lines).sum();
+
+            // share of commits and lines made in a window, relative to the deadline
+            let share = |from: i64, to: i64| {
+                let window: Vec<_> = activities.iter().filter(|a| a.time > milestone.due + from && a.time <= milestone.due + to).collect();
+                let window_lines: usize = window.iter().map(|a| a.lines).sum();
+                let percent = |part: usize, total: usize| if total == 0 { 0.0 } else { part as f64 / total as f64 * 100.0 };
+                format!("{:.0}% / {:.0}%", percent(window.len(), commits), percent(window_lines, lines))
+            };
+            let late = format!("{: <15}", share(0, late_hours * HOURS));  // padded first so the colour doesn't throw it off
+            let late = if activities.iter().any(|a| a.time > milestone.due) { late.red().to_string() } else { late };
+            println!("{0: <20} | {1: <10} | {2: <10} | {3: <15} | {4: <15} | {5}",
+                author, commits, lines, share(-48 * HOURS, 0), share(-24 * HOURS, 0), late);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> i64 {
+        Local.with_ymd_and_hms(y, m, d, h, min, s).earliest().unwrap().timestamp()
+    }
+
+    #[test]
+    fn dates_mean_the_start_or_end_of_the_day() {
+        assert_eq!(parse_time("2026-10-20 17:00", true), Some(local(2026, 10, 20, 17, 0, 0)));
+        assert_eq!(parse_time(" 2026-10-20 ", false), Some(local(2026, 10, 20, 0, 0, 0)));
+        assert_eq!(parse_time("2026-10-20", true), Some(local(2026, 10, 20, 23, 59, 59)));
+        assert_eq!(parse_time("20/10/2026", true), None);
+        assert_eq!(parse_time("2026-13-01", false), None);
+    }
+
+    #[test]
+    fn milestones_are_sorted_by_deadline() {
+        let settings = ["Sprint 2 due 2026-10-20".to_string(), "Sprint 1 due date due 2026-10-06 12:00".to_string(), "no deadline".to_string()];
+        let milestones = parse_milestones(&settings);
+        let names: Vec<&str> = milestones.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["Sprint 1 due date", "Sprint 2"]);
+        assert_eq!(milestones[0].due, local(2026, 10, 6, 12, 0, 0));
+    }
+}
@@ -0,0 +1,319 @@
+use crate::consts::{BLAME_IGNORE_REVS, WAW_IGNORE_REVS};
+
+use git2::{Repository, AttrCheckFlags, AttrValue, Oid, Error};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+// checks a path (relative to the repo root) against a simple gitignore-style pattern:
+// 'dir/' matches a directory anywhere in the path, patterns containing another '/' are matched
+// from the repo root, and anything else is matched against the file name ('*' and '?' are wildcards)
+pub fn matches_pattern(path: &str, pattern: &str) -> bool {
+    if let Some(dir) = pattern.strip_suffix('/') {
+        if dir.contains('/') {
+            wildcard_match(path, &format!("{}/*", dir.trim_start_matches('/')))
+        } else {
+            let parts: Vec<&str> = path.split('/').collect();
+            parts[..parts.len() - 1].iter().any(|part| wildcard_match(part, dir))
+        }
+    } else if pattern.contains('/') {
+        wildcard_match(path, pattern.trim_start_matches('/'))
+    } else {
+        wildcard_match(path.rsplit('/').next().unwrap_or(path), pattern)
+    }
+}
+
+// true if a file should be left out of line counts, either because .gitattributes marks it as
+// generated/vendored (linguist style) or because it matches one of the ignore patterns
+pub fn is_ignored(repo: &Repository, path: &str, patterns: &[String]) -> bool {
+    for attr in ["linguist-generated", "linguist-vendored"] {
+        match get_attr(repo, path, attr) {
+            AttrValue::True | AttrValue::String("true") => return true,
+            AttrValue::False | AttrValue::String("false") => return false,  // explicitly marked as hand written, so never ignore it
+            _ => (),
+        }
+    }
+    patterns.iter().any(|p| matches_pattern(path, p))
+}
+
+// '-diff' in .gitattributes tells git to treat a file as binary, so we do the same
+pub fn is_binary_attr(repo: &Repository, path: &str) -> bool {
+    matches!(get_attr(repo, path, "diff"), AttrValue::False)
+}
+
+fn get_attr<'a>(repo: &'a Repository, path: &str, name: &str) -> AttrValue<'a> {
+    AttrValue::from_string(repo.get_attr(Path::new(path), name, AttrCheckFlags::FILE_THEN_INDEX).ok().flatten())
+}
+
+// '*' matches any run of characters (including '/') and '?' matches exactly one
+fn wildcard_match(text: &str, pattern: &str) -> bool {
+    let text: Vec<char> = text.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+    let (mut t, mut p) = (0, 0);
+    let mut last_star: Option<(usize, usize)> = None;  // where to backtrack to if a match fails
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            t += 1;
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            last_star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = last_star {  // let the last star eat one more char
+            p = star_p + 1;
+            t = star_t + 1;
+            last_star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+// commits to skip entirely - the ones listed in .git-blame-ignore-revs (or whichever file
+// blame.ignoreRevsFile points to) and .waw-ignore-revs, plus any given with --ignore-rev.
+// revs are resolved to full ids so abbreviated SHAs, tags etc. work too
+pub fn get_ignored_revs(repo: &Repository, extra: &[String]) -> HashSet<Oid> {
+    let mut files = vec![BLAME_IGNORE_REVS.to_string(), WAW_IGNORE_REVS.to_string()];
+    if let Ok(path) = repo.config().and_then(|c| c.get_string("blame.ignoreRevsFile")) {
+        if !files.contains(&path) {
+            files.push(path);
+        }
+    }
+
+    let mut revs: Vec<String> = extra.to_vec();
+    for file in files {
+        if let Some(content) = read_repo_file(repo, &file) {
+            revs.extend(content.lines()
+                .map(|line| line.split('#').next().unwrap_or("").trim().to_string())  // same format as git: one rev per line, # for comments
+                .filter(|line| !line.is_empty()));
+        }
+    }
+
+    let mut ignored = HashSet::new();
+    for rev in revs {
+        match repo.revparse_single(&rev).and_then(|obj| obj.peel_to_commit()) {
+            Ok(commit) => { ignored.insert(commit.id()); },
+            Err(_) => println!("Couldn't find commit to ignore: {}", rev),
+        }
+    }
+
+    ignored
+}
+
+// reads a file from the working directory, or from HEAD if the repo is bare/the file isn't checked out
+pub fn read_repo_file(repo: &Repository, path: &str) -> Option<String> {
+    if let Some(content) = repo.workdir().and_then(|dir| fs::read_to_string(dir.join(path)).ok()) {
+        return Some(content);
+    }
+    let tree = repo.head().ok()?.peel_to_tree().ok()?;
+    let blob = tree.get_path(Path::new(path)).ok()?.to_object(repo).ok()?.peel_to_blob().ok()?;
+    String::from_utf8(blob.content().to_vec()).ok()
+}
+
+// pairs up reverts (found from git's default 'This reverts commit <sha>' message) with the commits
+// they undo, and returns both halves of each pair whose change isn't in the end result. Reverts can be
+// reverted too (eg. 'Reapply ...'), so a commit is only undone if one of its reverts wasn't itself undone.
+// Only commits being walked are paired up - with --merges first-parent a reverted branch commit is counted
+// as part of its merge, so the revert has to stay to take those lines back out
+pub fn find_reverts(repo: &Repository, commits: &[Oid]) -> Result<HashSet<Oid>, Error> {
+    let regex = Regex::new(r"This reverts commit ([0-9a-f]{7,40})").unwrap();
+    let walked: HashSet<&Oid> = commits.iter().collect();
+    let mut targets: HashMap<Oid, Oid> = HashMap::new();        // revert -> commit it reverts
+    let mut reverts: HashMap<Oid, Vec<Oid>> = HashMap::new();   // commit -> reverts of it
+    for &commit in commits {
+        let commit_obj = repo.find_commit(commit)?;
+        if let Some(captures) = regex.captures(commit_obj.message().unwrap_or("")) {
+            let original = repo.revparse_single(&captures[1]).and_then(|obj| obj.peel_to_commit()).ok();
+            if let Some(original) = original.filter(|o| walked.contains(&o.id())) {
+                targets.insert(commit, original.id());
+                reverts.entry(original.id()).or_default().push(commit);
+            }
+        }
+    }
+
+    let mut undone: HashMap<Oid, bool> = HashMap::new();
+    let mut reverted = HashSet::new();
+    for (&revert, &target) in &targets {
+        if is_undone(target, &reverts, &mut undone) {
+            reverted.insert(target);
+            reverted.insert(revert);
+        }
+    }
+
+    Ok(reverted)
+}
+
+// whether a commit's change has been taken back out by a revert that is still in effect
+fn is_undone(commit: Oid, reverts: &HashMap<Oid, Vec<Oid>>, undone: &mut HashMap<Oid, bool>) -> bool {
+    if let Some(&known) = undone.get(&commit) {
+        return known;
+    }
+    let result = reverts.get(&commit)
+        .map(|rs| rs.iter().any(|&r| !is_undone(r, reverts, undone)))
+        .unwrap_or(false);
+    undone.insert(commit, result);
+
+    result
+}
+
+// finds cherry-picked copies of commits by their patch-id (a hash of the diff that ignores line numbers
+// and whitespace, like 'git patch-id'). The copy with the oldest author date (then commit date, since
+// cherry-picks keep the author date) is taken to be the original, and every other copy is returned so it
+// can be skipped. Merges are left alone since they have no single diff
+pub fn find_cherry_picks(repo: &Repository, commits: &[Oid]) -> Result<HashSet<Oid>, Error> {
+    let mut originals: HashMap<Oid, ((i64, i64), Oid)> = HashMap::new();  // patch-id -> ((author time, commit time), commit)
+    let mut copies = HashSet::new();
+    for &commit in commits {
+        let commit_obj = repo.find_commit(commit)?;
+        if commit_obj.parent_count() > 1 {
+            continue;
+        }
+        let parent_tree = match commit_obj.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit_obj.tree()?), None)?;
+        if diff.deltas().len() == 0 {  // empty commits would all share a patch-id
+            continue;
+        }
+        let patch_id = diff.patchid(None)?;
+        let time = (commit_obj.author().when().seconds(), commit_obj.time().seconds());
+
+        match originals.get(&patch_id) {
+            Some(&(original_time, _)) if original_time <= time => { copies.insert(commit); },
+            Some(&(_, original)) => {  // found an older copy, so the one we had was a copy too
+                copies.insert(original);
+                originals.insert(patch_id, (time, commit));
+            },
+            None => { originals.insert(patch_id, (time, commit)); },
+        }
+    }
+
+    Ok(copies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_repo::TestRepo;
+    use git2::{Signature, Time};
+
+    fn revert_message(commit: Oid) -> String {
+        format!("Revert\n\nThis reverts commit {}.", commit)
+    }
+
+    #[test]
+    fn wildcards_match_any_run_or_one_character() {
+        assert!(wildcard_match("main.min.js", "*.min.js"));
+        assert!(wildcard_match("a/b/c.rs", "a/*.rs"));  // stars cross directories
+        assert!(wildcard_match("v1.txt", "v?.txt"));
+        assert!(!wildcard_match("v10.txt", "v?.txt"));
+        assert!(wildcard_match("", "*"));
+        assert!(!wildcard_match("abc", "abd*"));
+    }
+
+    #[test]
+    fn patterns_match_like_gitignore() {
+        assert!(matches_pattern("web/vendor/lib.js", "vendor/"));  // a directory anywhere
+        assert!(!matches_pattern("vendor", "vendor/"));  // but not a file with that name
+        assert!(matches_pattern("src/gen/api.rs", "src/gen/"));
+        assert!(!matches_pattern("lib/src/gen/api.rs", "/src/gen/"));  // anchored to the root
+        assert!(matches_pattern("src/schema.generated.ts", "*.generated.*"));  // file names at any depth
+        assert!(matches_pattern("Cargo.lock", "/Cargo.lock"));
+        assert!(!matches_pattern("sub/Cargo.lock", "/Cargo.lock"));
+    }
+
+    #[test]
+    fn gitattributes_override_patterns() {
+        let t = TestRepo::new("attributes");
+        fs::write(t.repo.workdir().unwrap().join(".gitattributes"), "gen.rs linguist-generated\nkeep.min.js -linguist-vendored\n").unwrap();
+        let patterns = vec!["*.min.js".to_string()];
+        assert!(is_ignored(&t.repo, "src/gen.rs", &patterns));
+        assert!(is_ignored(&t.repo, "app.min.js", &patterns));
+        assert!(!is_ignored(&t.repo, "keep.min.js", &patterns));  // marked as hand written
+        assert!(!is_ignored(&t.repo, "src/main.rs", &patterns));
+    }
+
+    #[test]
+    fn cherry_pick_keeps_the_oldest_copy() {
+        let mut t = TestRepo::new("cherry-pick");
+        let base = t.commit("alice", &[], &[("f.txt", Some("a\n"))], "base");
+        let original = t.commit("bob", &[base], &[("g.txt", Some("g\n"))], "fix");
+        let main = t.commit("alice", &[base], &[("h.txt", Some("h\n"))], "main");
+        let picked = t.commit("alice", &[main], &[("g.txt", Some("g\n"))], "fix (picked)");
+
+        // walked newest first, the original turns up after its copy
+        assert_eq!(find_cherry_picks(&t.repo, &[picked, main, original, base]).unwrap(), HashSet::from([picked]));
+        assert_eq!(find_cherry_picks(&t.repo, &[original, picked, main, base]).unwrap(), HashSet::from([picked]));
+    }
+
+    #[test]
+    fn cherry_pick_with_the_same_author_date_keeps_the_first_committed() {
+        let mut t = TestRepo::new("cherry-pick-date");
+        let base = t.commit("alice", &[], &[("f.txt", Some("a\n"))], "base");
+        let original = t.commit("bob", &[base], &[("g.txt", Some("g\n"))], "fix");
+        let main = t.commit("alice", &[base], &[("h.txt", Some("h\n"))], "main");
+        let applied = t.commit("alice", &[main], &[("g.txt", Some("g\n"))], "tmp");
+        let tree = t.repo.find_commit(applied).unwrap().tree().unwrap();
+
+        // a real cherry-pick keeps the original's author (and date) but is committed later
+        let original_obj = t.repo.find_commit(original).unwrap();
+        let committer = Signature::new("alice", "alice@example.com", &Time::new(1_900_000_000, 0)).unwrap();
+        let picked = t.repo.commit(None, &original_obj.author(), &committer, "fix", &tree, &[&t.repo.find_commit(main).unwrap()]).unwrap();
+
+        assert_eq!(find_cherry_picks(&t.repo, &[picked, main, original, base]).unwrap(), HashSet::from([picked]));
+    }
+
+    #[test]
+    fn empty_commits_are_not_cherry_picks() {
+        let mut t = TestRepo::new("cherry-pick-empty");
+        let base = t.commit("alice", &[], &[("f.txt", Some("a\n"))], "base");
+        let first = t.commit("alice", &[base], &[], "empty");
+        let second = t.commit("bob", &[first], &[], "also empty");
+
+        assert!(find_cherry_picks(&t.repo, &[second, first, base]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn revert_drops_both_commits() {
+        let mut t = TestRepo::new("revert");
+        let base = t.commit("alice", &[], &[("f.txt", Some("a\n"))], "base");
+        let bad = t.commit("bob", &[base], &[("f.txt", Some("b\n"))], "bad");
+        let revert = t.commit("bob", &[bad], &[("f.txt", Some("a\n"))], &revert_message(bad));
+
+        let reverted = find_reverts(&t.repo, &[revert, bad, base]).unwrap();
+        assert_eq!(reverted, HashSet::from([bad, revert]));
+    }
+
+    #[test]
+    fn revert_of_unwalked_commit_is_kept() {
+        let mut t = TestRepo::new("revert-branch");
+        let base = t.commit("alice", &[], &[("f.txt", Some("a\n"))], "base");
+        let bad = t.commit("bob", &[base], &[("g.txt", Some("b\n"))], "bad");
+        let merge = t.commit("alice", &[base, bad], &[("g.txt", Some("b\n"))], "merge");
+        let revert = t.commit("alice", &[merge], &[("g.txt", None)], &revert_message(bad));
+
+        // with --merges first-parent the branch commit is counted as part of the merge, so the revert stays too
+        assert!(find_reverts(&t.repo, &[revert, merge, base]).unwrap().is_empty());
+        assert_eq!(find_reverts(&t.repo, &[revert, merge, bad, base]).unwrap(), HashSet::from([bad, revert]));
+    }
+
+    #[test]
+    fn reapplied_commit_is_kept() {
+        let mut t = TestRepo::new("reapply");
+        let base = t.commit("alice", &[], &[("f.txt", Some("a\n"))], "base");
+        let change = t.commit("bob", &[base], &[("f.txt", Some("b\n"))], "change");
+        let revert = t.commit("bob", &[change], &[("f.txt", Some("a\n"))], &revert_message(change));
+        let reapply = t.commit("bob", &[revert], &[("f.txt", Some("b\n"))], &revert_message(revert));
+
+        let reverted = find_reverts(&t.repo, &[reapply, revert, change, base]).unwrap();
+        assert_eq!(reverted, HashSet::from([revert, reapply]));  // the revert and reapply cancel out
+
+        let again = t.commit("bob", &[reapply], &[("f.txt", Some("a\n"))], &revert_message(reapply));
+        let reverted = find_reverts(&t.repo, &[again, reapply, revert, change, base]).unwrap();
+        assert_eq!(reverted, HashSet::from([change, revert, reapply, again]));
+    }
+}
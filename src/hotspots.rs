@@ -0,0 +1,72 @@
+use crate::consts::MAX_COUPLING_FILES;
+use crate::stats::CommitStats;
+
+use colored::Colorize;
+use std::collections::HashMap;
+
+// how often each file changes, who changes it, and which files change together
+#[derive(Default)]
+pub struct Hotspots {
+    files: HashMap<String, (usize, HashMap<String, usize>)>,  // file -> (commits, author -> commits)
+    pairs: HashMap<(String, String), usize>,                  // (file, file) -> commits changing both
+}
+
+impl Hotspots {
+    pub fn add_commit(&mut self, authors: &[String], stats: &CommitStats) {
+        let mut paths: Vec<&String> = stats.files.iter().map(|f| &f.path).collect();
+        paths.sort();
+        paths.dedup();
+        for path in &paths {
+            let (commits, file_authors) = self.files.entry(path.to_string()).or_default();
+            *commits += 1;
+            for author in authors {
+                *file_authors.entry(author.clone()).or_insert(0) += 1;
+            }
+        }
+
+        // huge commits (mass renames, reformatting etc.) would couple everything with everything
+        if paths.len() <= MAX_COUPLING_FILES {
+            for (i, a) in paths.iter().enumerate() {
+                for b in &paths[i + 1..] {
+                    *self.pairs.entry((a.to_string(), b.to_string())).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    // prints the n most changed files with their main authors, then the n file pairs that change together most
+    pub fn print(&self, n: usize) {
+        let mut files: Vec<_> = self.files.iter().collect();
+        files.sort_by(|a, b| b.1.0.cmp(&a.1.0).then(a.0.cmp(b.0)));
+
+        let width = 50 + 3 + 10 + 3 + 40 + 1;
+        println!();
+        println!("{:-<width$}", "");
+        println!("{0: <50} | {1: <10} | {2: <40}", "file".yellow(), "commits".yellow(), "main authors (commits)".yellow());
+        println!("{:-<width$}", "");
+        for (path, (commits, authors)) in files.iter().take(n) {
+            let mut authors: Vec<(&String, &usize)> = authors.iter().collect();
+            authors.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+            let main_authors: Vec<String> = authors.iter()
+                .take(3)
+                .map(|(author, count)| format!("{} ({})", author, count))
+                .collect();
+            println!("{0: <50} | {1: <10} | {2: <40}", path, commits, main_authors.join(", "));
+        }
+
+        // coupling is how often the pair changes together compared to how often the files change on average
+        let mut pairs: Vec<(&(String, String), &usize)> = self.pairs.iter().filter(|(_, &count)| count > 1).collect();
+        pairs.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+        let width = 80 + 3 + 15 + 3 + 10 + 1;
+        println!();
+        println!("{:-<width$}", "");
+        println!("{0: <80} | {1: <15} | {2: <10}", "files changed together".yellow(), "shared commits".yellow(), "coupling".yellow());
+        println!("{:-<width$}", "");
+        for ((a, b), shared) in pairs.iter().take(n) {
+            let average = (self.files[a].0 + self.files[b].0) as f64 / 2.0;
+            let coupling = format!("{:.0}%", **shared as f64 / average * 100.0);
+            println!("{0: <80} | {1: <15} | {2: <10}", format!("{} + {}", a, b), shared, coupling);
+        }
+    }
+}
@@ -13,6 +13,7 @@ pub fn process_flags(flags: String, args: &mut Vec<String>) -> Result<(), io::Er
             'd' if !used_args => { used_args = true; delete_alias(args, false)? },
             'a' if !used_args => { used_args = true; add_alias(args)? },
             'p' if !used_args => { used_args = true; set_path(args)? },
+            's' if !used_args => { used_args = true; add_setting(args)? },
             bad  => println!("Invalid flag/combination: {}", bad),
         };
     };
@@ -21,7 +22,11 @@ pub fn process_flags(flags: String, args: &mut Vec<String>) -> Result<(), io::Er
 }
 
 // sets option flags and assigns the correct arguments to the relevant variables
-pub fn enable_options(flags: String, mut new_args: Vec<String>, options: &mut Vec<bool>, path: &mut String, time_seconds: &mut i64, arg_vector: &mut Vec<Vec<String>>) -> () {
+pub fn enable_options(flags: String, mut new_args: Vec<String>, options: &mut [bool], path: &mut String, time_seconds: &mut i64, arg_vector: &mut [Vec<String>]) {
+    if let Some(long) = flags.strip_prefix('-') {  // --long-options use the whole word rather than one char per option
+        enable_long_option(long, new_args, options, arg_vector);
+        return;
+    }
     let mut used_args = false;
     for f in flags.chars() {
         match f {
@@ -32,7 +37,7 @@ pub fn enable_options(flags: String, mut new_args: Vec<String>, options: &mut Ve
             'P' if !used_args => {  // manually provide Path rather than use one in config
                 used_args = true; 
                 options[1] = true;
-                if new_args.len() == 0 {  // maybe we should panic here so it doesn't just use saved one
+                if new_args.is_empty() {  // maybe we should panic here so it doesn't just use saved one
                     println!("No path argument provided");
                 } else if new_args.len() == 1 {
                     *path = new_args.pop().unwrap();
@@ -86,3 +91,167 @@ pub fn enable_options(flags: String, mut new_args: Vec<String>, options: &mut Ve
         }
     };
 }
+
+// sets options given as a whole word (eg. --ignore) and stores their arguments
+fn enable_long_option(name: &str, mut new_args: Vec<String>, options: &mut [bool], arg_vector: &mut [Vec<String>]) {
+    match name {
+        "ignore" => {  // extra path patterns to leave out of line counts for this run
+            arg_vector.get_mut(IGNORE).unwrap().append(&mut new_args);
+        },
+        "no-default-ignore" => options[10] = true,  // count lockfiles, vendored code etc. after all
+        "ignore-rev" => {  // commits to leave out, on top of .git-blame-ignore-revs
+            arg_vector.get_mut(IGNORE_REVS).unwrap().append(&mut new_args);
+        },
+        "renames" => {  // similarity threshold (%) for counting a file as renamed/copied
+            match new_args.first().map(|t| t.parse::<u16>()) {
+                Some(Ok(threshold)) if new_args.len() == 1 && threshold <= 100 => arg_vector[RENAMES] = new_args,
+                _ => println!("enter a similarity percentage between 0 and 100"),
+            }
+        },
+        "no-renames" => options[12] = true,  // count renamed files as a delete plus an add
+        "whitespace" => {  // whitespace changes to ignore when counting lines
+            if !new_args.is_empty() && new_args.iter().all(|w| ["all", "change", "eol", "blank"].contains(&w.as_str())) {
+                arg_vector.get_mut(WHITESPACE).unwrap().append(&mut new_args);
+            } else {
+                println!("enter any of all, change, eol or blank");
+            }
+        },
+        "reverts" => {  // what to do with commits that were reverted and the reverts themselves
+            match new_args.first().map(|r| r.as_str()) {
+                Some("drop") if new_args.len() == 1 => options[15] = true,
+                Some("separate") if new_args.len() == 1 => options[16] = true,
+                _ => println!("enter either drop or separate"),
+            }
+        },
+        "dedupe" => options[17] = true,  // count cherry-picked changes once, for the original commit
+        "surviving" => {  // blame every file to see how many lines each author still owns
+            options[18] = true;
+            if new_args.len() > 1 {
+                println!("Too many arguments");
+            } else {
+                arg_vector[SURVIVING] = new_args;  // revision to blame at (HEAD if not given)
+            }
+        },
+        "breakdown" => {  // each author's contributions per file, or per directory down to a given depth
+            options[19] = true;
+            match new_args.first().map(|d| d.parse::<usize>()) {
+                None => (),
+                Some(Ok(depth)) if new_args.len() == 1 && depth > 0 => arg_vector[BREAKDOWN] = new_args,
+                _ => println!("enter a directory depth of at least 1 (or nothing to break down by file)"),
+            }
+        },
+        "languages" => options[20] = true,  // each author's contributions per language/file type
+        "tests" => options[21] = true,  // lines added/deleted in test code and the test to code ratio
+        "churn" => {  // how much of each author's new code gets rewritten within a number of days
+            options[22] = true;
+            match new_args.first().map(|d| d.parse::<i64>()) {
+                None => (),
+                Some(Ok(days)) if new_args.len() == 1 && days >= 0 => arg_vector[CHURN] = new_args,
+                _ => println!("enter a number of days"),
+            }
+        },
+        "matrix" => {  // who changes lines written by who, as a table, graphviz dot or json
+            options[23] = true;
+            match new_args.first().map(|f| f.as_str()) {
+                None => (),
+                Some("table" | "dot" | "json") if new_args.len() == 1 => arg_vector[MATRIX] = new_args,
+                _ => println!("enter one of table, dot or json"),
+            }
+        },
+        "hotspots" => {  // most changed files and the files that change together most
+            options[24] = true;
+            match new_args.first().map(|n| n.parse::<usize>()) {
+                None => (),
+                Some(Ok(_)) if new_args.len() == 1 => arg_vector[HOTSPOTS] = new_args,
+                _ => println!("enter the number of files to show"),
+            }
+        },
+        "bus-factor" => {  // how few people own most of each directory
+            options[25] = true;
+            let depth_ok = new_args.first().map(|d| d.parse::<usize>().map(|d| d > 0).unwrap_or(false)).unwrap_or(true);
+            let share_ok = new_args.get(1).map(|s| s.parse::<f64>().map(|s| (0.0..100.0).contains(&s)).unwrap_or(false)).unwrap_or(true);
+            if depth_ok && share_ok && new_args.len() <= 2 {
+                arg_vector[BUS_FACTOR] = new_args;
+            } else {
+                println!("enter a directory depth of at least 1, optionally followed by a percentage below 100");
+            }
+        },
+        "codeowners" => {  // write a suggested CODEOWNERS file, or check the existing one against history
+            match new_args.first().map(|c| c.as_str()) {
+                Some("write") if new_args.len() <= 2 => options[26] = true,  // optionally followed by where to write it
+                Some("check") if new_args.len() == 1 => options[27] = true,
+                Some("check") if new_args.len() == 2 && new_args[1].parse::<i64>().is_ok() => options[27] = true,  // months
+                _ => println!("enter write (optionally followed by a file path) or check (optionally followed by a number of months)"),
+            }
+            arg_vector[CODEOWNERS] = new_args.into_iter().skip(1).collect();
+        },
+        "timeline" => {  // each author's commits and lines per day/week/month
+            options[28] = true;
+            match new_args.iter().map(|a| a.as_str()).collect::<Vec<&str>>().as_slice() {
+                [] => (),
+                ["day" | "week" | "month"] | ["day" | "week" | "month", "table" | "spark" | "bars"] => arg_vector[TIMELINE] = new_args,
+                _ => println!("enter one of day, week or month, optionally followed by one of table, spark or bars"),
+            }
+        },
+        "calendar" => {  // github-style heatmap of commits per day, for each author or the whole team
+            options[29] = true;
+            let team = new_args.first().map(|a| a == "team").unwrap_or(false);
+            if new_args.len() > 1 + team as usize {
+                println!("enter team and/or the path of an svg file to write to (or nothing to show each author in the terminal)");
+            } else {
+                arg_vector[CALENDAR] = new_args;
+            }
+        },
+        "work-patterns" => options[30] = true,  // when each author commits, by hour and weekday
+        "hours" => {  // estimated hours worked, from grouping commits into sessions
+            options[31] = true;
+            if new_args.len() <= 2 && new_args.iter().all(|m| m.parse::<i64>().map(|m| m >= 0).unwrap_or(false)) {
+                arg_vector[SESSIONS] = new_args;
+            } else {
+                println!("enter the longest gap in minutes between commits in the same session, optionally followed by the minutes to allow for the start of each session");
+            }
+        },
+        "deadlines" => {  // how much of each milestone's work was left until the last day or two, or was late
+            options[32] = true;
+            match new_args.first().map(|h| h.parse::<i64>()) {
+                None => (),
+                Some(Ok(hours)) if new_args.len() == 1 && hours >= 0 => arg_vector[DEADLINES] = new_args,
+                _ => println!("enter the number of hours after a deadline that still count as late"),
+            }
+        },
+        "sprints" => options[33] = true,  // each author's stats per sprint set in the config
+        "releases" => {  // each author's contributions between each pair of tags, as a table or markdown
+            options[34] = true;
+            match new_args.first().map(|f| f.as_str()) {
+                None => (),
+                Some("table" | "markdown") if new_args.len() == 1 => arg_vector[RELEASES] = new_args,
+                _ => println!("enter either table or markdown"),
+            }
+        },
+        "changelog" => {  // markdown changelog from conventional commits between two revisions
+            options[35] = true;
+            if new_args.len() > 2 {
+                println!("enter the revision to start from (the last tag if not given), optionally followed by the one to end at");
+            } else {
+                arg_vector[CHANGELOG] = new_args;
+            }
+        },
+        "formatting" => {  // what to do with commits that only reformat code
+            match new_args.first().map(|f| f.as_str()) {
+                Some("skip") if new_args.len() == 1 => options[13] = true,
+                Some("separate") if new_args.len() == 1 => options[14] = true,
+                _ => println!("enter either skip or separate"),
+            }
+        },
+        "skip-roots" => options[11] = true,  // don't count commits with no parent (eg. importing a template)
+        "merges" => {  // how merge commits are counted (by default they are diffed against their first parent)
+            match new_args.first().map(|m| m.as_str()) {
+                Some("skip" | "only" | "resolutions" | "first-parent") if new_args.len() == 1 => {
+                    arg_vector[MERGES] = new_args;
+                },
+                _ => println!("enter one of skip, only, resolutions or first-parent"),
+            }
+        },
+        bad => panic!("Invalid option: --{}", bad),
+    }
+}
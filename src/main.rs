@@ -1,13 +1,43 @@
 mod input_handler;
 mod consts;
 mod config_use;
+mod filters;
+mod stats;
+mod authors;
+mod blame;
+mod breakdown;
+mod collaboration;
+mod hotspots;
+mod ownership;
+mod timeline;
+mod calendar;
+mod deadlines;
+mod sprints;
+mod releases;
+mod changelog;
+#[cfg(test)]
+mod test_repo;
 
+use filters::{get_ignored_revs, find_reverts, find_cherry_picks, read_repo_file};
+use authors::{AuthorCache, get_authors, split_message};
+use blame::{file_owners, deleted_line_origins};
+use breakdown::{Breakdown, path_key, classify, add_commit_breakdown, print_breakdown};
+use collaboration::{Matrix, print_matrix};
+use hotspots::Hotspots;
+use ownership::{print_bus_factor, write_codeowners, check_codeowners};
+use timeline::{Activity, ActivityLog, print_timeline, print_work_patterns, estimate_minutes};
+use calendar::{print_calendar, write_calendar_svg};
+use deadlines::{parse_milestones, print_deadlines};
+use sprints::{parse_sprints, find_sprint, print_sprints};
+use releases::{find_releases, print_releases};
+use changelog::print_changelog;
 use input_handler::{process_flags, enable_options};
 use consts::*;
 use config_use::*;
+use stats::{AuthorStats, DiffSettings, tree_diff, commit_stats, merge_resolution_stats, is_formatting_only};
 
-use git2::{Repository, Error};
-use std::collections::HashMap;
+use git2::{Repository, Oid, Error};
+use std::collections::{HashMap, HashSet};
 use std::env;
 use regex::Regex;
 use chrono::Local;
@@ -19,9 +49,7 @@ use colored::Colorize;
 // can change the new config continuously, which would also include the stats you want displayed, and an
 // option to 'export' and save in a file at the end of your session
 fn main() -> Result<(), Error> {
-    let untagged = UNTAGGED.to_string();
-
-    let mut options: Vec<bool> = vec![false; 10];
+    let mut options: Vec<bool> = vec![false; 36];
     let mut args: Vec<String> = env::args().skip(1).collect();  // skips the first redundant argument
 
     let mut time_seconds: i64 = 0;
@@ -31,11 +59,11 @@ fn main() -> Result<(), Error> {
         Err(e) => panic!("Error finding path: {}", e),
     };
 
-    let mut arg_vector = vec![vec![]; 5];  // stores option args set with user flags
+    let mut arg_vector = vec![vec![]; 23];  // stores option args set with user flags
     let mut first = true;
     let mut flags = String::new();
     
-    if args.len() > 0 && args[0].starts_with("-c") {  // config editing mode
+    if !args.is_empty() && args[0].starts_with("-c") {  // config editing mode
         let flags = args.remove(0);
         match process_flags(String::from(&flags[2..]), &mut args) {
             Ok(_) => {},
@@ -66,12 +94,12 @@ fn main() -> Result<(), Error> {
             Err(e) => panic!("Couldn't find repo: {}", e),
         };
 
-        let mut commit_counter: HashMap<String, (usize, usize, usize, Vec<usize>)> = HashMap::new();
+        let mut commit_counter: HashMap<String, AuthorStats> = HashMap::new();
         let mut rw = match repo.revwalk() {  // this lets us traverse the commit graph
             Ok(rw) => rw,
             Err(e) => {
                 println!("Error creating revwalk: {}", e);
-                return Err(e.into());
+                return Err(e);
              }
         };
 
@@ -80,158 +108,469 @@ fn main() -> Result<(), Error> {
             Err(e) => panic!("Couldn't parse config file: {}", e),
         };
 
+        let mut ignore: Vec<String> = vec![];  // path patterns to leave out of line counts
+        if !options[10] {
+            ignore.extend(DEFAULT_IGNORE.iter().map(|p| p.to_string()));
+        }
+        match get_setting("ignore") {
+            Ok(patterns) => ignore.extend(patterns),
+            Err(e) => panic!("Couldn't parse config file: {}", e),
+        };
+        ignore.extend(arg_vector[IGNORE].iter().cloned());
+
+        let similarity = match arg_vector[RENAMES].first() {  // how similar (%) files have to be to count as renamed/copied
+            _ if options[12] => None,
+            Some(threshold) => Some(threshold.parse::<u16>().expect("Failed to parse string to int")),
+            None => Some(50),  // same default as git
+        };
+        let settings = DiffSettings { ignore, similarity, whitespace: arg_vector[WHITESPACE].clone() };
+
         let pattern = r"\[([^,\]]+)(?:, ([^,\]]+))*\]";  // matches authors when commit message looks like:
         let regex = Regex::new(pattern).unwrap();        // [user1, user2, user3 ...] conv_com_msg: blah blah
+        if options[35] {  // the changelog is its own thing rather than an extra table
+            return print_changelog(&repo, arg_vector[CHANGELOG].first(), arg_vector[CHANGELOG].get(1), &config_map, &options, &regex);
+        }
 
-        let _ = rw.push_head()?;
-        for commit in rw.filter_map(|x| x.ok()) {  // iterate over commit graph with revwalk
-            let commit_obj = repo.find_commit(commit)?;
-            let parent_commit = match commit_obj.parent(0) {  // parent is needed to use diff to check lines modified since last commit
-                Ok(parent) => parent,
-                Err(_)  => commit_obj.clone(),  // initial commit has no parent
-            };
-            // get stats pertaining to changes since last commit
-            let stats = repo.diff_tree_to_tree(Some(&parent_commit.tree()?), Some(&commit_obj.tree()?), None)?.stats()?;
-            let author_name = match commit_obj.committer().name() {  // get author of current commit
-                Some(name) => name.to_string(),
-                None => "ERROR".to_string(),
-            };
+        let ignored_revs = get_ignored_revs(&repo, &arg_vector[IGNORE_REVS]);
+        let mut breakdown: Breakdown = HashMap::new();  // per file/directory contributions, if asked for
+        let depth = arg_vector[BREAKDOWN].first().map(|d| d.parse::<usize>().expect("Failed to parse string to int"));
+        let mut languages: Breakdown = HashMap::new();  // per language contributions, if asked for
+        let language_overrides = match get_setting("languages") {
+            Ok(overrides) => overrides,
+            Err(e) => panic!("Couldn't parse config file: {}", e),
+        };
+        let mut sprint_breakdown: Breakdown = HashMap::new();  // per sprint contributions, if asked for
+        let sprints = match get_setting("sprints") {
+            Ok(sprints) if options[33] => parse_sprints(&sprints),
+            Ok(_) => vec![],
+            Err(e) => panic!("Couldn't parse config file: {}", e),
+        };
+        let test_patterns: Vec<String> = match get_setting("tests") {  // configured test conventions replace the defaults
+            Ok(patterns) if !patterns.is_empty() => patterns,
+            Ok(_) => DEFAULT_TESTS.iter().map(|t| t.to_string()).collect(),
+            Err(e) => panic!("Couldn't parse config file: {}", e),
+        };
+
+        let merges = arg_vector[MERGES].first().map(|m| m.as_str()).unwrap_or("");
+        rw.push_head()?;
+        let mut release_breakdown: Breakdown = HashMap::new();  // per release contributions, if asked for
+        let (releases, release_of) = if options[34] {
+            find_releases(&repo)?
+        } else {
+            (vec![], HashMap::new())
+        };
+        if merges == "first-parent" {  // only follow the mainline, so each merged branch shows up as its merge commit
+            rw.simplify_first_parent()?;
+        }
+        let commits: Vec<Oid> = rw.filter_map(|x| x.ok()).collect();  // collected first so we can look ahead for reverts
+        // commits only in tags that aren't in HEAD's history are walked too, but only count towards releases
+        let tag_only: Vec<Oid> = if releases.is_empty() {
+            vec![]
+        } else {
+            let mut tag_rw = repo.revwalk()?;
+            for release in &releases {
+                tag_rw.push(release.commit)?;
+            }
+            tag_rw.hide_head()?;
+            if merges == "first-parent" {
+                tag_rw.simplify_first_parent()?;
+            }
+            tag_rw.filter_map(|x| x.ok()).collect()
+        };
+        let reverted = if options[15] || options[16] {
+            find_reverts(&repo, &commits)?
+        } else {
+            HashSet::new()
+        };
+        let cherry_picks = if options[17] {
+            find_cherry_picks(&repo, &commits)?
+        } else {
+            HashSet::new()
+        };
 
-            let (data, msg) = match commit_obj.message() {  // split at colon to get 'data' (type of commit and contributors)
-                Some(commit_msg) => match commit_msg.split_once(":") {
-                    Some((data, msg)) => (data, msg),
-                    _ => ("", commit_msg),
-                },
-                None => ("", ""),  // probably shouldn't end up here even if there is no colon
+        let mut author_cache = AuthorCache::new(&repo, &config_map, &options, &regex);
+        let mut churn: HashMap<String, (usize, usize)> = HashMap::new();  // author -> (lines they rewrote themselves, lines others rewrote) soon after adding them
+        let churn_seconds = match arg_vector[CHURN].first() {
+            Some(days) => days.parse::<i64>().expect("Failed to parse string to int") * DAYS,
+            None => 14 * DAYS,
+        };
+        let mut matrix: Matrix = HashMap::new();  // who changes whose code
+        let mut hotspots = Hotspots::default();
+        let mut last_touched: HashMap<String, HashMap<String, i64>> = HashMap::new();  // author -> (file -> last time they changed it)
+        let mut activity: ActivityLog = HashMap::new();
+
+        let head_commits = commits.len();
+        for (i, commit) in commits.into_iter().chain(tag_only).enumerate() {  // iterate over commit graph from the revwalk
+            let release_only = i >= head_commits;
+            if ignored_revs.contains(&commit) {  // eg. mass reformatting that git blame also skips
+                continue;
+            }
+            if options[15] && reverted.contains(&commit) {  // drop bad commits along with their reverts
+                continue;
+            }
+            if cherry_picks.contains(&commit) {  // only the original of a cherry-picked change is counted
+                continue;
+            }
+            let commit_obj = repo.find_commit(commit)?;
+            if options[11] && commit_obj.parent_count() == 0 {  // leave out initial imports/orphan branch roots
+                continue;
+            }
+            let is_merge = commit_obj.parent_count() > 1;
+            match merges {
+                "skip" if is_merge => continue,
+                "only" if !is_merge => continue,
+                _ => (),
+            }
+            // in first-parent mode a merge stands in for the whole branch, so credit whoever wrote the branch
+            // (the tip of the merged branch) rather than whoever pressed the merge button
+            let credit_obj = if merges == "first-parent" && is_merge {
+                commit_obj.parent(1)?
+            } else {
+                commit_obj.clone()
             };
-            
-            let mut found = false;
+            let (data, msg) = split_message(&credit_obj);
             // These boolean values will be true if either a specific option is not enabled, or if the condition is met by this commit
             let filtered = !options[3] || arg_vector[FILTERS].iter().any(|f| data.contains(f));
             let case_insensitive = !options[4] || arg_vector[CI_SEARCH].iter().any(|s| data.to_lowercase().contains(&s.to_lowercase()) || msg.to_lowercase().contains(&s.to_lowercase()));
             let searched = !options[7] || arg_vector[SEARCH].iter().any(|s| data.contains(s) || msg.contains(s));
             let timed = !options[9] || current_time - commit_obj.time().seconds() <= time_seconds;
+            if !(filtered && case_insensitive && searched && timed) {
+                continue;
+            }
+            // exclude has to be done after finding the authors so that we can exclude autogenerated names or aliases
+            let authors: Vec<String> = get_authors(&credit_obj, &config_map, &options, &regex)
+                .into_iter()
+                .filter(|author| !options[6] || !arg_vector[EXCLUDE].contains(author))
+                .collect();
+            if authors.is_empty() {
+                continue;
+            }
 
-            if options[5] {  // using autogenerated config with commit message data
-                if let Some(captures) = regex.captures(data) {
-                    for cap in captures.iter().skip(1) {
-                        if let Some(author) = cap {
-                            let author_s = author.as_str().to_string();
-                            let excluded = !options[6] || !arg_vector[EXCLUDE].contains(&author_s);  // exclude has to be done after the other options so
-                            if filtered && case_insensitive && excluded && searched && timed {       // that we can exclude autogenerated names or aliases
-                                let counter = commit_counter.entry(author_s).or_insert((0, 0, 0, vec![]));
-                                counter.0 += 1;
-                                counter.1 += stats.insertions();
-                                counter.2 += stats.deletions();
-                                counter.3.push(stats.insertions() + stats.deletions());
+            let parent_tree = match commit_obj.parent(0) {  // parent is needed to use diff to check lines modified since last commit
+                Ok(parent) => Some(parent.tree()?),
+                Err(_)  => None,  // root commits have no parent, so everything in them is new (diff against the empty tree)
+            };
+            // get stats pertaining to changes since last commit
+            let diff = tree_diff(&repo, parent_tree.as_ref(), &commit_obj.tree()?, &settings)?;
+            let resolutions = merges == "resolutions" && is_merge;
+            // whether the commit only reformats code (eg. cargo fmt)
+            let formatting = !resolutions && (options[13] || options[14]) && is_formatting_only(&repo, &diff, &settings)?;
+            let mut stats = if resolutions {
+                merge_resolution_stats(&repo, &commit_obj, &settings)?
+            } else {
+                commit_stats(&repo, &diff, &settings)?
+            };
+            if options[21] {
+                stats.count_tests(&test_patterns);
+            }
+            if formatting && options[13] {
+                continue;
+            } else if formatting && options[14] {
+                stats.move_to_formatting();
+            }
+            if options[16] && reverted.contains(&commit) {
+                stats.move_to_reverted();
+            }
+
+            if release_only {
+                let release = release_of.get(&commit).map(|r| r.as_str()).unwrap_or(UNRELEASED);
+                for author in authors {
+                    add_commit_breakdown(&mut release_breakdown, &author, &stats, |_| release.to_string());
+                }
+                continue;
+            }
+
+            // blame the lines this commit deletes to see who wrote them (merges would blame the same
+            // lines as the commits they merge, so they are skipped - except in first-parent mode, where the
+            // merge's diff against the mainline stands in for the branch's commits)
+            if (options[22] || options[23]) && (!is_merge || merges == "first-parent") {
+                if let Ok(parent) = commit_obj.parent(0) {
+                    for (origin, lines) in deleted_line_origins(&repo, &diff, &parent, &settings)? {
+                        let (time, original_authors) = author_cache.get(origin)?;
+                        let recent = commit_obj.time().seconds() - time <= churn_seconds;
+                        for original_author in original_authors {
+                            if options[22] && recent && !formatting {
+                                let counter = churn.entry(original_author.clone()).or_insert((0, 0));
+                                if authors.contains(original_author) {
+                                    counter.0 += lines;
+                                } else {
+                                    counter.1 += lines;
+                                }
+                            }
+                            if options[23] {
+                                for author in &authors {
+                                    *matrix.entry(author.clone()).or_default().entry(original_author.clone()).or_insert(0) += lines;
+                                }
                             }
                         }
                     }
-                } else {  // no contributors listed in the expected format
-                    // choose how to deal with this - maybe ignore or have an unknown
-                    if !options[6] || !arg_vector[EXCLUDE].contains(&untagged) {
-                        let counter = commit_counter.entry(untagged.clone()).or_insert((0, 0, 0, vec![]));
-                        counter.0 += 1;
-                        counter.1 += stats.insertions();
-                        counter.2 += stats.deletions();
-                        counter.3.push(stats.insertions() + stats.deletions());
+                }
+            }
+
+            if options[24] {
+                hotspots.add_commit(&authors, &stats);
+            }
+            if options[27] {
+                for author in &authors {
+                    let files = last_touched.entry(author.clone()).or_default();
+                    for file in &stats.files {
+                        let time = files.entry(file.path.clone()).or_insert(0);
+                        *time = (*time).max(commit_obj.time().seconds());
                     }
                 }
-            } else {
-                if !options[2] {  // only do this if we are not ignorning the config
-                    for (alias, names) in &config_map {
-                        let alias_s = alias.to_string();
-                        let excluded = !options[6] || !arg_vector[EXCLUDE].contains(&alias_s);
-                        if names.contains(&author_name) || author_name == *alias {
-                            if filtered && case_insensitive && excluded && searched && timed {
-                                let counter = commit_counter.entry(alias_s).or_insert((0, 0, 0, vec![]));
-                                counter.0 += 1;
-                                counter.1 += stats.insertions();
-                                counter.2 += stats.deletions();
-                                counter.3.push(stats.insertions() + stats.deletions());
-                            }
-                            found = true;
-                        }
-                    };
+            }
+            for author in authors {
+                if options[19] {
+                    add_commit_breakdown(&mut breakdown, &author, &stats, |path| path_key(path, depth));
+                }
+                if options[20] {
+                    add_commit_breakdown(&mut languages, &author, &stats, |path| classify(path, &language_overrides, &test_patterns));
                 }
-                if !found && !options[0] {  // author_name is not an alias or in the config (or we ignored config)
-                    let excluded = !options[6] || !arg_vector[EXCLUDE].contains(&author_name);
-                    if filtered && case_insensitive && excluded && searched && timed {
-                        let counter = commit_counter.entry(author_name).or_insert((0, 0, 0, vec![]));
-                        counter.0 += 1;
-                        counter.1 += stats.insertions();
-                        counter.2 += stats.deletions();
-                        counter.3.push(stats.insertions() + stats.deletions());
+                if options[33] {
+                    if let Some(sprint) = find_sprint(&sprints, credit_obj.author().when().seconds()) {
+                        add_commit_breakdown(&mut sprint_breakdown, &author, &stats, |_| sprint.name.clone());
                     }
                 }
+                if options[34] {
+                    let release = release_of.get(&commit).map(|r| r.as_str()).unwrap_or(UNRELEASED);
+                    add_commit_breakdown(&mut release_breakdown, &author, &stats, |_| release.to_string());
+                }
+                if options[28] || options[29] || options[30] || options[31] || options[32] {
+                    let when = credit_obj.author().when();
+                    activity.entry(author.clone()).or_default().push(Activity {
+                        commit,
+                        time: when.seconds(),
+                        offset: when.offset_minutes(),
+                        lines: stats.insertions + stats.deletions,
+                    });
+                }
+                commit_counter.entry(author).or_default().add_commit(&stats);
+            }
+        }
+
+        // lines each author still owns, from blaming every file at the given revision
+        let owners = if options[18] || options[25] || options[26] {
+            let rev = arg_vector[SURVIVING].first().map(|r| r.as_str()).unwrap_or("HEAD");
+            file_owners(&repo, rev, &settings, &mut author_cache)?
+        } else {
+            HashMap::new()
+        };
+        if options[18] {
+            for (author, lines) in owners.values().flatten() {
+                if let Some(data) = commit_counter.get_mut(author) {  // only for authors that are in the table
+                    data.surviving += lines;
+                }
             }
         }
-        print_results(commit_counter);
+        for (author, (self_churn, reworked)) in churn {
+            if let Some(data) = commit_counter.get_mut(&author) {
+                data.self_churn += self_churn;
+                data.reworked += reworked;
+            }
+        }
+        if options[31] {
+            let gap = arg_vector[SESSIONS].first().map(|m| m.parse::<i64>().expect("Failed to parse string to int")).unwrap_or(120);
+            let allowance = arg_vector[SESSIONS].get(1).map(|m| m.parse::<i64>().expect("Failed to parse string to int")).unwrap_or(30);
+            for (author, activities) in &activity {
+                if let Some(data) = commit_counter.get_mut(author) {
+                    data.minutes = estimate_minutes(activities, gap, allowance);
+                }
+            }
+        }
+        let bus_depth = arg_vector[BUS_FACTOR].first().map(|d| d.parse::<usize>().expect("Failed to parse string to int")).unwrap_or(1);
+        let bus_share = arg_vector[BUS_FACTOR].get(1).map(|s| s.parse::<f64>().expect("Failed to parse string to float")).unwrap_or(50.0) / 100.0;
+        if options[26] || options[27] {  // CODEOWNERS are handled on their own, like dot/json output
+            let handles: HashMap<String, String> = match get_setting("handles") {  // alias -> GitHub handle
+                Ok(handles) => handles.iter().filter_map(|h| h.split_once('=')).map(|(a, h)| (a.to_string(), h.to_string())).collect(),
+                Err(e) => panic!("Couldn't parse config file: {}", e),
+            };
+            if options[26] {
+                write_codeowners(&owners, bus_depth, bus_share, &handles, arg_vector[CODEOWNERS].first());
+            } else {
+                let months = arg_vector[CODEOWNERS].first().map(|m| m.parse::<i64>().expect("Failed to parse string to int")).unwrap_or(6);
+                match ["CODEOWNERS", ".github/CODEOWNERS", "docs/CODEOWNERS"].iter().find_map(|p| read_repo_file(&repo, p)) {
+                    Some(content) => check_codeowners(&content, &last_touched, &handles, current_time - months * MONTHS),
+                    None => println!("No CODEOWNERS file found"),
+                }
+            }
+            return Ok(());
+        }
+        let matrix_format = arg_vector[MATRIX].first().map(|f| f.as_str()).unwrap_or("table");
+        if options[23] && matrix_format != "table" {  // dot/json are printed on their own so they can be piped somewhere
+            print_matrix(&matrix, matrix_format);
+            return Ok(());
+        }
+        let release_format = arg_vector[RELEASES].first().map(|f| f.as_str()).unwrap_or("table");
+        if options[34] && release_format == "markdown" {  // printed on its own so it can go straight into release notes
+            print_releases(&release_breakdown, &releases, release_format);
+            return Ok(());
+        }
+        print_results(commit_counter, &options);
+        if options[19] {
+            print_breakdown(&breakdown, if depth.is_some() { "directory" } else { "file" });
+        }
+        if options[20] {
+            print_breakdown(&languages, "language");
+        }
+        if options[23] {
+            print_matrix(&matrix, matrix_format);
+        }
+        if options[25] {
+            print_bus_factor(&owners, bus_depth, bus_share);
+        }
+        if options[24] {
+            hotspots.print(arg_vector[HOTSPOTS].first().map(|n| n.parse::<usize>().expect("Failed to parse string to int")).unwrap_or(10));
+        }
+        if options[28] {
+            let period = arg_vector[TIMELINE].first().map(|p| p.as_str()).unwrap_or("week");
+            print_timeline(&activity, period, arg_vector[TIMELINE].get(1).map(|f| f.as_str()).unwrap_or("table"));
+        }
+        if options[29] {
+            let team = arg_vector[CALENDAR].first().map(|a| a == "team").unwrap_or(false);
+            match arg_vector[CALENDAR].iter().find(|a| *a != "team") {
+                Some(path) => write_calendar_svg(&activity, team, path),
+                None => print_calendar(&activity, team),
+            }
+        }
+        if options[30] {
+            print_work_patterns(&activity);
+        }
+        if options[34] {
+            print_releases(&release_breakdown, &releases, release_format);
+        }
+        if options[33] {
+            if sprints.is_empty() {
+                println!("No sprints set (add them with eg. waw -cs sprints \"Sprint 1 from 2026-09-01 to 2026-09-14\")");
+            }
+            print_sprints(&sprint_breakdown, &sprints);
+        }
+        if options[32] {
+            let milestones = match get_setting("milestones") {
+                Ok(milestones) => parse_milestones(&milestones),
+                Err(e) => panic!("Couldn't parse config file: {}", e),
+            };
+            if milestones.is_empty() {
+                println!("No milestones set (add them with eg. waw -cs milestones \"Sprint 2 due 2026-10-20 17:00\")");
+            }
+            let late_hours = arg_vector[DEADLINES].first().map(|h| h.parse::<i64>().expect("Failed to parse string to int")).unwrap_or(24);
+            print_deadlines(&activity, &milestones, late_hours);
+        }
     }
 
     Ok(())
 }
 
 // function to print all the stats that I decided you might want in a nice, formatted, coloured table
-fn print_results(mut commit_counter: HashMap<String, (usize, usize, usize, Vec<usize>)>) {
+fn print_results(mut commit_counter: HashMap<String, AuthorStats>, options: &[bool]) {
     // TODO print what filters and such have been used
     // println!("Commits by each user (using/not using config with/without filters, exclusions, searches etc.): \n");
     // TODO only display stats user asks for, add more things
-    let mut all_data: [Vec<usize>; 5] = Default::default();
-
-    // how much space is allocated for each column
-    let spacing = vec![20, 10, 15, 15, 25, 20];
-
-    println!("{:-<121}", "");
-    println!( // {arg_no: <char_width}
-        "{0: <20} | {1: <10} | {2: <15} | {3: <15} | {4: <25} | {5: <20}",  // TODO replace magic numbers with spacing[n]
-        "author".yellow(), "commits".yellow(), "lines added".yellow(), "lines deleted".yellow(), "lines modified per commit".yellow(), "median lines modified".yellow()
-    );
-    println!("{:-<121}", "");
-    for (_, data) in &mut commit_counter {
-        let (commits, ins, dels, ref mut lines) = data;
-
-        all_data[0].push(*commits);
-        all_data[1].push(*ins);
-        all_data[2].push(*dels);
-        all_data[3].push((*ins + *dels) / *commits);
-
-        lines.sort();
-        let median = lines[lines.len()/2];  // probably not efficient to store all these but what can you do
-        all_data[4].push(median);
+
+    // header and how much space is allocated for each column after the author column
+    let mut columns = vec![
+        ("commits", 10), ("lines added", 15), ("lines deleted", 15), ("lines modified per commit", 25),
+        ("median lines modified", 20), ("binary files", 12), ("renames", 10),
+    ];
+    if options[14] {
+        columns.push(("formatting lines", 16));
+    }
+    if options[16] {
+        columns.push(("reverted lines", 14));
+    }
+    if options[18] {
+        columns.push(("surviving lines", 15));
+    }
+    if options[21] {
+        columns.extend([("test lines added", 16), ("test lines deleted", 18), ("test/code ratio", 15)]);
+    }
+    if options[22] {
+        columns.extend([("self churn", 10), ("reworked by others", 18), ("churn rate", 10)]);
+    }
+    if options[31] {
+        columns.push(("estimated hours", 15));
+    }
+    let mut all_data: Vec<Vec<f64>> = vec![vec![]; columns.len()];
+    let width = columns.iter().fold(20, |acc, (_, w)| acc + w + 3) + 1;
+
+    println!("{:-<width$}", "");
+    print!("{: <20}", "author".yellow());
+    for &(header, w) in &columns {
+        print!(" | {: <w$}", header.yellow());
+    }
+    println!();
+    println!("{:-<width$}", "");
+    for data in commit_counter.values_mut() {
+        data.lines.sort();
+        let median = data.lines[data.lines.len()/2];  // probably not efficient to store all these but what can you do
+
+        let mut row = vec![data.commits, data.insertions, data.deletions, (data.insertions + data.deletions) / data.commits, median, data.binary, data.renames];
+        if options[14] {
+            row.push(data.formatting);
+        }
+        if options[16] {
+            row.push(data.reverted);
+        }
+        if options[18] {
+            row.push(data.surviving);
+        }
+        if options[21] {
+            row.extend([data.test_insertions, data.test_deletions]);
+        }
+        let mut row: Vec<f64> = row.iter().map(|&value| value as f64).collect();
+        if options[21] {  // test lines added per line of production code added
+            let code = (data.insertions - data.test_insertions) as f64;
+            let ratio = if data.test_insertions == 0 { 0.0 } else { data.test_insertions as f64 / code };
+            // only tests and no code has no ratio - NaN is shown as n/a and left out of the max/min
+            row.push(if code == 0.0 && data.test_insertions > 0 { f64::NAN } else { (ratio * 100.0).round() / 100.0 });
+        }
+        if options[22] {  // churn rate is the share of the lines they added that got rewritten soon after
+            let churned = data.self_churn + data.reworked;
+            let rate = if churned == 0 || data.insertions == 0 { 0.0 } else { churned as f64 / data.insertions as f64 };
+            row.extend([data.self_churn as f64, data.reworked as f64, (rate * 100.0).round() / 100.0]);
+        }
+        if options[31] {
+            row.push((data.minutes as f64 / 60.0 * 10.0).round() / 10.0);
+        }
+        for (i, value) in row.iter().enumerate() {
+            all_data[i].push(*value);
+        }
     }
 
     // this next bit colours the data green or red if it is the max/min by storing what we are going
-    // to print for each bit of data in a vec of Vec<String> - either we store the original value 
-    // converted to a string, or that string but coloured if it is a max/min. not the best but does the trick
+    // to print for each bit of data in a vec of Vec<String> - either we store the original value
+    // converted to a string (padded to the column width), or that string but coloured if it is a max/min
     let mut string_data: Vec<Vec<String>> = all_data
     .iter()
-    .map(|x| x
+    .zip(&columns)
+    .map(|(x, &(_, w))| x
         .iter()
-        .map(|y| y.to_string())
+        .map(|y| if y.is_nan() { format!("{: <w$}", "n/a") } else { format!("{: <w$}", y) })
         .collect()
     )
     .collect();
 
     // this isn't great - maxima and minima could possibly be tracked and updated in the
     // original pass of the data (in the revwalk) rather than making a second pass
-    // (though this would result in fewer checks and changes to the max/mins...)
-    for i in 0..(all_data.len()) {
-        if all_data[i].len() > 0 {
-            let min = *all_data[i].iter().min().unwrap();
-            let max = *all_data[i].iter().max().unwrap();
-            
-            for (count, (name, _)) in commit_counter.iter().enumerate() {
-                if all_data[i][count] == max {                            // i+1 to skip author column
-                    string_data[i][count] = string_data[i][count].green().to_string() + &" ".repeat(spacing[i+1] -  string_data[i][count].len())
-                } else if all_data[i][count] == min {  // add repeated spaces here because colouring screws up column formatting (so we do it manually)
-                    string_data[i][count] = string_data[i][count].red().to_string() + &" ".repeat(spacing[i+1] -  string_data[i][count].len())
-                }
-                    if i == all_data.len() - 1 {  // after processing the final entry, we will have our max/min and therefore can print the data
-                    println!("{0: <20} | {1: <10} | {2: <15} | {3: <15} | {4: <25} | {5: <20}", 
-                        name, string_data[0][count], string_data[1][count], string_data[2][count], string_data[3][count], string_data[4][count]);
+    for i in 0..all_data.len() {
+        if !all_data[i].is_empty() {
+            let min = all_data[i].iter().cloned().fold(f64::INFINITY, f64::min);
+            let max = all_data[i].iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+            for count in 0..all_data[i].len() {  // pad before colouring so the colour codes don't screw up the columns
+                if all_data[i][count] == max {
+                    string_data[i][count] = string_data[i][count].green().to_string();
+                } else if all_data[i][count] == min {
+                    string_data[i][count] = string_data[i][count].red().to_string();
                 }
             }
+        }
     }
+
+    for (count, name) in commit_counter.keys().enumerate() {  // now we have our max/mins we can print the data
+        print!("{: <20}", name);
+        for column in &string_data {
+            print!(" | {}", column[count]);
+        }
+        println!();
     }
 }
@@ -0,0 +1,300 @@
+use crate::breakdown::path_key;
+
+use chrono::DateTime;
+use colored::Colorize;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+
+// file -> (author -> lines), from blame
+pub type Owners = HashMap<String, HashMap<String, usize>>;
+
+// adds up file ownership for each directory down to depth levels
+pub fn directory_owners(owners: &Owners, depth: usize) -> Owners {
+    let mut directories: Owners = HashMap::new();
+    for (path, file_owners) in owners {
+        let directory = directories.entry(path_key(path, Some(depth))).or_default();
+        for (author, lines) in file_owners {
+            *directory.entry(author.clone()).or_insert(0) += lines;
+        }
+    }
+
+    directories
+}
+
+// the smallest number of authors that together own more than share (0-1) of the lines, along with
+// every author sorted by how much they own
+pub fn bus_factor(owners: &HashMap<String, usize>, share: f64) -> (usize, Vec<(&String, &usize)>) {
+    let mut authors: Vec<(&String, &usize)> = owners.iter().collect();
+    authors.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let total: usize = owners.values().sum();
+
+    let mut owned = 0;
+    let mut factor = 0;
+    for (_, lines) in &authors {
+        if owned as f64 > share * total as f64 {
+            break;
+        }
+        owned += *lines;
+        factor += 1;
+    }
+
+    (factor, authors)
+}
+
+// prints the bus factor of each directory, flagging the ones a single person owns
+pub fn print_bus_factor(owners: &Owners, depth: usize, share: f64) {
+    let directories = directory_owners(owners, depth);
+    let mut paths: Vec<&String> = directories.keys().collect();
+    paths.sort();
+
+    let width = 40 + 3 + 10 + 3 + 10 + 3 + 50 + 1;
+    println!();
+    println!("{:-<width$}", "");
+    println!("{0: <40} | {1: <10} | {2: <10} | {3: <50}",
+        "directory".yellow(), "lines".yellow(), "bus factor".yellow(), "owners (share of lines)".yellow());
+    println!("{:-<width$}", "");
+    for path in paths {
+        let total: usize = directories[path].values().sum();
+        if total == 0 {
+            continue;
+        }
+        let (factor, authors) = bus_factor(&directories[path], share);
+        let shares: Vec<String> = authors.iter()
+            .take(4)
+            .map(|(author, lines)| format!("{} ({:.0}%)", author, **lines as f64 / total as f64 * 100.0))
+            .collect();
+        let factor = if factor == 1 {  // knowledge silo
+            format!("{: <10}", factor).red().to_string()
+        } else {
+            format!("{: <10}", factor)
+        };
+        println!("{0: <40} | {1: <10} | {2} | {3: <50}", path, total, factor, shares.join(", "));
+    }
+}
+
+// writes a CODEOWNERS file (or prints it if path is None) where each directory is owned by the authors
+// that make up its bus factor. authors are turned into GitHub handles using the handles setting
+pub fn write_codeowners(owners: &Owners, depth: usize, share: f64, handles: &HashMap<String, String>, path: Option<&String>) {
+    let directories = directory_owners(owners, depth);
+    let mut paths: Vec<&String> = directories.keys().collect();
+    paths.sort_by_key(|p| (*p != "./", p.to_string()));  // the catch-all rule has to come first as the last match wins
+
+    let mut content = String::from("# Suggested by waw from git blame - each path is owned by the fewest authors who own most of it\n");
+    for dir in paths {
+        let (factor, authors) = bus_factor(&directories[dir], share);
+        let mut rule_owners = vec![];
+        let mut missing = vec![];
+        for (author, _) in authors.iter().take(factor) {
+            match handles.get(*author) {
+                Some(handle) => rule_owners.push(handle.clone()),
+                None => missing.push(author.to_string()),
+            }
+        }
+        if !missing.is_empty() {
+            content += &format!("# no handle set for: {}\n", missing.join(", "));
+        }
+        if !rule_owners.is_empty() {
+            let pattern = if dir == "./" { "*".to_string() } else { format!("/{}", dir) };
+            content += &format!("{} {}\n", pattern, rule_owners.join(" "));
+        }
+    }
+
+    match path {
+        Some(path) => match fs::write(path, content) {
+            Ok(_) => println!("Wrote suggested CODEOWNERS to {}", path),
+            Err(e) => panic!("Couldn't write CODEOWNERS file: {}", e),
+        },
+        None => print!("{}", content),
+    }
+}
+
+// checks each owner in an existing CODEOWNERS file against history, and reports the owners who haven't
+// changed anything their rule covers since the given time. last_touched is author -> (file -> time)
+pub fn check_codeowners(content: &str, last_touched: &HashMap<String, HashMap<String, i64>>, handles: &HashMap<String, String>, since: i64) {
+    let width = 40 + 3 + 30 + 3 + 12 + 3 + 10 + 1;
+    println!();
+    println!("{:-<width$}", "");
+    println!("{0: <40} | {1: <30} | {2: <12} | {3: <10}", "path".yellow(), "owner".yellow(), "last change".yellow(), "status".yellow());
+    println!("{:-<width$}", "");
+
+    for (pattern, owner, last) in owner_last_changes(content, last_touched, handles) {
+        let (date, status) = match last {
+            Some(time) if time >= since => (format_date(time), "ok".green()),
+            Some(time) => (format_date(time), "stale".red()),
+            None => ("never".to_string(), "stale".red()),
+        };
+        println!("{0: <40} | {1: <30} | {2: <12} | {3: <10}", pattern, owner, date, status);
+    }
+}
+
+// (pattern, owner, when the owner last changed a file the rule covers) for every owner of every rule
+fn owner_last_changes<'a>(content: &'a str, last_touched: &HashMap<String, HashMap<String, i64>>,
+                          handles: &HashMap<String, String>) -> Vec<(&'a str, &'a str, Option<i64>)> {
+    let rules: Vec<Vec<&str>> = content.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.split_whitespace().collect())
+        .collect();
+    // like git, the last rule matching a file is the one that owns it
+    let rule_for = |file: &str| rules.iter().rposition(|rule| codeowners_match(file, rule[0]));
+
+    let mut changes = vec![];
+    for (i, rule) in rules.iter().enumerate() {
+        for &owner in &rule[1..] {
+            // handles map back to their alias, otherwise assume the handle is the author's name
+            let author = handles.iter()
+                .find(|(_, handle)| handle.as_str() == owner)
+                .map(|(alias, _)| alias.as_str())
+                .unwrap_or(owner.trim_start_matches('@'));
+            let last = last_touched.get(author)
+                .and_then(|files| files.iter()
+                    .filter(|(file, _)| rule_for(file) == Some(i))
+                    .map(|(_, &time)| time)
+                    .max());
+            changes.push((rule[0], owner, last));
+        }
+    }
+
+    changes
+}
+
+// whether a CODEOWNERS (gitignore style) pattern covers a file. A leading '/' or a '/' in the middle anchors
+// the pattern to the repo root, otherwise it can match a file or directory name at any depth. Matching a
+// directory covers everything under it, and a trailing '/' only matches directories. '*' and '?' don't match
+// '/', but '**' does. Like GitHub, a pattern ending in a '*'/'?' glob (eg. 'docs/*') only matches files
+// directly in it rather than whole directories
+pub fn codeowners_match(path: &str, pattern: &str) -> bool {
+    let dir_only = pattern.ends_with('/');
+    let anchored = pattern.starts_with('/') || pattern.trim_end_matches('/').contains('/');
+    let last = pattern.trim_end_matches('/').rsplit('/').next().unwrap_or("");
+    let files_only = !dir_only && last.contains(['*', '?']) && !last.contains("**");
+    let regex = match Regex::new(&format!("^{}$", glob_to_regex(pattern.trim_start_matches('/').trim_end_matches('/')))) {
+        Ok(regex) => regex,
+        Err(_) => return false,
+    };
+
+    let parts: Vec<&str> = path.split('/').collect();
+    for end in 1..=parts.len() {
+        if dir_only && end == parts.len() {  // the whole path is the file itself, not a directory
+            break;
+        }
+        if files_only && end < parts.len() {
+            continue;
+        }
+        let matched = if anchored {
+            regex.is_match(&parts[..end].join("/"))
+        } else {
+            regex.is_match(parts[end - 1])
+        };
+        if matched {
+            return true;
+        }
+    }
+
+    false
+}
+
+// turns a glob into a regex where '*' and '?' stay within a directory and '**' can span several
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {  // '**/' is zero or more directories
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            },
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex
+}
+
+pub fn format_date(time: i64) -> String {
+    match DateTime::from_timestamp(time, 0) {
+        Some(date) => date.format("%Y-%m-%d").to_string(),
+        None => time.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn factor(lines: &[(&str, usize)]) -> usize {
+        let owners: HashMap<String, usize> = lines.iter().map(|(a, l)| (a.to_string(), *l)).collect();
+        bus_factor(&owners, 0.5).0
+    }
+
+    #[test]
+    fn bus_factor_needs_more_than_the_share() {
+        assert_eq!(factor(&[("alice", 50), ("bob", 50)]), 2);  // exactly half isn't more than half
+        assert_eq!(factor(&[("alice", 60), ("bob", 40)]), 1);
+        assert_eq!(factor(&[("alice", 10)]), 1);
+        assert_eq!(factor(&[("alice", 40), ("bob", 30), ("carol", 30)]), 2);
+        assert_eq!(factor(&[]), 0);
+    }
+
+    #[test]
+    fn bus_factor_sorts_owners_by_lines_then_name() {
+        let owners: HashMap<String, usize> = HashMap::from([("carol".to_string(), 5), ("bob".to_string(), 20), ("alice".to_string(), 5)]);
+        let (_, authors) = bus_factor(&owners, 0.5);
+        let names: Vec<&str> = authors.iter().map(|(a, _)| a.as_str()).collect();
+        assert_eq!(names, ["bob", "alice", "carol"]);
+    }
+
+    #[test]
+    fn leading_slash_anchors_to_root() {
+        assert!(codeowners_match("docs/a.md", "/docs"));
+        assert!(!codeowners_match("src/docs/a.md", "/docs"));
+        assert!(codeowners_match("README.md", "/README.md"));
+        assert!(!codeowners_match("src/README.md", "/README.md"));
+    }
+
+    #[test]
+    fn bare_name_matches_at_any_depth() {
+        assert!(codeowners_match("docs/a.md", "docs"));
+        assert!(codeowners_match("src/docs/a.md", "docs"));
+        assert!(codeowners_match("src/lib/main.rs", "*.rs"));
+        assert!(!codeowners_match("src/lib/main.rs", "lib.rs"));
+    }
+
+    #[test]
+    fn directory_pattern_matches_everything_under_it() {
+        assert!(codeowners_match("src/a/b/c.rs", "src/"));
+        assert!(codeowners_match("lib/src/c.rs", "src/"));
+        assert!(!codeowners_match("src", "src/"));  // a file called src isn't a directory
+        assert!(codeowners_match("apps/web/x.ts", "apps/web/"));
+        assert!(!codeowners_match("lib/apps/web/x.ts", "apps/web/"));  // slash in the middle anchors it
+    }
+
+    #[test]
+    fn stars_stay_within_directories() {
+        assert!(codeowners_match("docs/a.md", "docs/*"));
+        assert!(!codeowners_match("docs/a/b.md", "docs/*"));  // only files directly in docs/
+        assert!(codeowners_match("docs/a/b.md", "docs/**"));
+        assert!(!codeowners_match("docs/a/b.md", "docs/*.md"));
+        assert!(codeowners_match("docs/a/b.md", "docs/**/*.md"));
+        assert!(codeowners_match("docs/b.md", "docs/**/*.md"));
+        assert!(codeowners_match("anything/at/all", "*"));
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let mut last_touched: HashMap<String, HashMap<String, i64>> = HashMap::new();
+        last_touched.entry("alice".to_string()).or_default().insert("docs/a.md".to_string(), 100);
+        last_touched.entry("bob".to_string()).or_default().insert("src/a.rs".to_string(), 200);
+        let changes = owner_last_changes("* @alice @bob\n/docs/ @alice\n", &last_touched, &HashMap::new());
+        // docs/a.md belongs to the /docs/ rule, so alice hasn't changed anything '*' still covers
+        assert_eq!(changes, vec![("*", "@alice", None), ("*", "@bob", Some(200)), ("/docs/", "@alice", Some(100))]);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::breakdown::{Breakdown, Contribution};
+use crate::consts::UNRELEASED;
+use crate::ownership::format_date;
+
+use git2::{Repository, Oid, Error};
+use colored::Colorize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+// a tag and the one before it in version order, with the commit it points to
+pub struct Release {
+    pub name: String,
+    pub previous: Option<String>,
+    pub commit: Oid,
+    pub time: i64,
+}
+
+// compares tags like versions, so v0.10.0 comes after v0.2.0 and v1.0.0-rc1 comes before v1.0.0
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let key = |tag: &str| {
+        let tag = tag.trim_start_matches(|c: char| !c.is_ascii_digit());
+        let end = tag.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(tag.len());
+        let numbers: Vec<u64> = tag[..end].split('.').filter_map(|n| n.parse().ok()).collect();
+        let rest = tag[end..].to_string();
+        (numbers, rest.is_empty(), rest)  // anything after the numbers is a pre-release, so comes first
+    };
+    key(a).cmp(&key(b)).then(a.cmp(b))
+}
+
+// every tag pointing (maybe through an annotated tag) at a commit, in version order, along with which release
+// each commit first appeared in
+pub fn find_releases(repo: &Repository) -> Result<(Vec<Release>, HashMap<Oid, String>), Error> {
+    let mut tags: Vec<(String, Oid)> = vec![];
+    for name in repo.tag_names(None)?.iter().flatten() {
+        let commit = repo.revparse_single(&format!("refs/tags/{}", name)).and_then(|o| o.peel_to_commit());
+        if let Ok(commit) = commit {  // tags of trees/blobs can't be a release
+            tags.push((name.to_string(), commit.id()));
+        }
+    }
+    tags.sort_by(|a, b| compare_versions(&a.0, &b.0));
+
+    let mut releases: Vec<Release> = vec![];
+    let mut release_of: HashMap<Oid, String> = HashMap::new();
+    for (i, (name, commit)) in tags.iter().enumerate() {
+        let mut rw = repo.revwalk()?;
+        rw.push(*commit)?;
+        if i > 0 {
+            rw.hide(tags[i - 1].1)?;
+        }
+        for id in rw.filter_map(|x| x.ok()) {
+            release_of.entry(id).or_insert(name.clone());  // an earlier release keeps commits it already had
+        }
+        releases.push(Release {
+            name: name.clone(),
+            previous: i.checked_sub(1).map(|p| tags[p].0.clone()),
+            commit: *commit,
+            time: repo.find_commit(*commit)?.time().seconds(),
+        });
+    }
+
+    Ok((releases, release_of))
+}
+
+// prints each author's contributions to each release (newest first, after anything unreleased) as tables
+// or a markdown document for release notes
+pub fn print_releases(breakdown: &Breakdown, releases: &[Release], format: &str) {
+    let mut sections: Vec<(String, String)> = vec![(UNRELEASED.to_string(), format!("changes since {}", releases.last().map(|r| r.name.as_str()).unwrap_or("the start")))];
+    for release in releases.iter().rev() {
+        let since = match &release.previous {
+            Some(previous) => format!("changes since {}", previous),
+            None => "first release".to_string(),
+        };
+        sections.push((release.name.clone(), format!("{}, {}", format_date(release.time), since)));
+    }
+
+    for (name, description) in sections {
+        let mut rows: Vec<(&String, &Contribution)> = breakdown.iter().filter_map(|(a, r)| r.get(&name).map(|c| (a, c))).collect();
+        if rows.is_empty() {
+            continue;
+        }
+        rows.sort_by(|a, b| (b.1.insertions + b.1.deletions).cmp(&(a.1.insertions + a.1.deletions)).then(a.0.cmp(b.0)));
+
+        if format == "markdown" {
+            println!("## {}", name);
+            println!();
+            println!("_{}_", description);
+            println!();
+            println!("| Author | Commits | Lines added | Lines deleted |");
+            println!("| --- | ---: | ---: | ---: |");
+            for (author, data) in rows {
+                println!("| {} | {} | {} | {} |", author.replace('|', "\\|"), data.commits, data.insertions, data.deletions);
+            }
+            println!();
+        } else {
+            let width = 20 + 3 + 10 + 3 + 15 + 3 + 15 + 1;
+            println!();
+            println!("{} ({})", name.yellow(), description);
+            println!("{:-<width$}", "");
+            println!("{0: <20} | {1: <10} | {2: <15} | {3: <15}", "author".yellow(), "commits".yellow(), "lines added".yellow(), "lines deleted".yellow());
+            println!("{:-<width$}", "");
+            for (author, data) in rows {
+                println!("{0: <20} | {1: <10} | {2: <15} | {3: <15}", author, data.commits, data.insertions, data.deletions);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert_eq!(compare_versions("v0.10.0", "v0.2.0"), Ordering::Greater);
+        assert_eq!(compare_versions("v1.2", "v1.2.1"), Ordering::Less);
+        assert_eq!(compare_versions("release-2", "v10"), Ordering::Less);
+    }
+
+    #[test]
+    fn pre_releases_come_first() {
+        assert_eq!(compare_versions("v1.0.0-rc1", "v1.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("v1.0.0-rc2", "v1.0.0-rc1"), Ordering::Greater);
+        assert_eq!(compare_versions("v1.0.0-rc1", "v0.9.0"), Ordering::Greater);
+    }
+}
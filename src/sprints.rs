@@ -0,0 +1,120 @@
+use crate::breakdown::{Breakdown, Contribution};
+use crate::consts::DAYS;
+use crate::deadlines::parse_time;
+
+use colored::Colorize;
+
+// a named period from the config, like 'Sprint 1 from 2026-09-01 to 2026-09-14' or
+// 'Sprint 2 from 2026-09-15 for 14 days'
+pub struct Sprint {
+    pub name: String,
+    pub start: i64,
+    pub end: i64,
+}
+
+// turns 'name from start to end' and 'name from start for n days/weeks' settings into sprints in order
+pub fn parse_sprints(settings: &[String]) -> Vec<Sprint> {
+    let mut sprints: Vec<Sprint> = vec![];
+    for setting in settings {
+        match setting.rsplit_once(" from ").and_then(|(name, range)| parse_range(range).map(|(start, end)| (name, start, end))) {
+            Some((name, start, end)) => sprints.push(Sprint { name: name.trim().to_string(), start, end }),
+            None => println!("Couldn't read sprint '{}' (expected 'name from YYYY-MM-DD to YYYY-MM-DD' or 'name from YYYY-MM-DD for n days')", setting),
+        }
+    }
+    sprints.sort_by_key(|s| s.start);
+
+    sprints
+}
+
+// start and end times of 'start to end' or 'start for n days/weeks'
+fn parse_range(range: &str) -> Option<(i64, i64)> {
+    if let Some((start, end)) = range.split_once(" to ") {
+        return Some((parse_time(start, false)?, parse_time(end, true)?));
+    }
+    let (start, length) = range.split_once(" for ")?;
+    let (n, unit) = length.trim().split_once(' ')?;
+    let days = match unit {
+        "day" | "days" => n.parse::<i64>().ok()?,
+        "week" | "weeks" => n.parse::<i64>().ok()? * 7,
+        _ => return None,
+    };
+    let start = parse_time(start, false)?;
+
+    Some((start, start + days * DAYS - 1))
+}
+
+// the sprint a time falls in (the first one, if they overlap)
+pub fn find_sprint(sprints: &[Sprint], time: i64) -> Option<&Sprint> {
+    sprints.iter().find(|s| s.start <= time && time <= s.end)
+}
+
+// prints each author's commits, lines added and lines deleted in each sprint side by side, with an arrow
+// showing whether it went up or down since the sprint before
+pub fn print_sprints(breakdown: &Breakdown, sprints: &[Sprint]) {
+    let mut authors: Vec<&String> = breakdown.keys().collect();
+    authors.sort();
+    let cell = sprints.iter().map(|s| s.name.len()).max().unwrap_or(0).max(12);
+
+    for measure in ["commits", "lines added", "lines deleted"] {
+        let value = |c: &Contribution| match measure {
+            "commits" => c.commits,
+            "lines added" => c.insertions,
+            _ => c.deletions,
+        };
+        let width = 20 + (cell + 3) * sprints.len() + 1;
+        println!();
+        println!("{}", measure.yellow());
+        println!("{:-<width$}", "");
+        print!("{: <20}", "author".yellow());
+        for sprint in sprints {
+            print!(" | {: <cell$}", sprint.name.yellow());
+        }
+        println!();
+        println!("{:-<width$}", "");
+        for author in &authors {
+            print!("{: <20}", author);
+            let mut previous: Option<usize> = None;
+            for sprint in sprints {
+                let current = breakdown[*author].get(&sprint.name).map(value).unwrap_or(0);
+                let (arrow, colour): (&str, fn(String) -> String) = match previous {
+                    Some(p) if current > p => ("↑", |s| s.green().to_string()),
+                    Some(p) if current < p => ("↓", |s| s.red().to_string()),
+                    Some(_) => ("→", |s| s),
+                    None => (" ", |s| s),
+                };
+                print!(" | {}", colour(format!("{: <cell$}", format!("{} {}", current, arrow))));  // padded before colouring
+                previous = Some(current);
+            }
+            println!();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_end_at_the_end_of_the_last_day() {
+        let start = parse_time("2026-09-01", false).unwrap();
+        let end = parse_time("2026-09-14", true).unwrap();
+        assert_eq!(parse_range("2026-09-01 to 2026-09-14"), Some((start, end)));
+        assert_eq!(parse_range("2026-09-01 for 14 days"), Some((start, start + 14 * DAYS - 1)));
+        assert_eq!(parse_range("2026-09-01 for 2 weeks"), Some((start, start + 14 * DAYS - 1)));
+        assert_eq!(parse_range("2026-09-01 for 2 months"), None);
+        assert_eq!(parse_range("2026-09-01"), None);
+    }
+
+    #[test]
+    fn sprints_are_sorted_and_found_by_time() {
+        let settings = ["Sprint 2 from 2026-09-15 for 2 weeks".to_string(), "Sprint 1 from 2026-09-01 to 2026-09-14".to_string(), "Sprint 3".to_string()];
+        let sprints = parse_sprints(&settings);
+        let names: Vec<&str> = sprints.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Sprint 1", "Sprint 2"]);
+
+        let first_day = parse_time("2026-09-15", false).unwrap();
+        assert_eq!(find_sprint(&sprints, first_day).map(|s| s.name.as_str()), Some("Sprint 2"));
+        assert_eq!(find_sprint(&sprints, first_day - 1).map(|s| s.name.as_str()), Some("Sprint 1"));
+        assert!(find_sprint(&sprints, parse_time("2026-10-01", false).unwrap()).is_none());
+    }
+}
@@ -0,0 +1,500 @@
+use crate::consts::{SINGLE_QUOTE_STRINGS, HASH_COMMENTS};
+use crate::filters::{is_ignored, is_binary_attr, matches_pattern};
+
+use git2::{Repository, Commit, Tree, Diff, DiffOptions, DiffFindOptions, Delta, Patch, Error};
+use std::collections::{HashMap, HashSet};
+
+// everything (set by the options) that changes how diffs are made and counted
+pub struct DiffSettings {
+    pub ignore: Vec<String>,      // path patterns to leave out of line counts
+    pub similarity: Option<u16>,  // threshold for rename/copy detection, None turns it off
+    pub whitespace: Vec<String>,  // kinds of whitespace changes to ignore (all, change, eol, blank)
+}
+
+// line counts for a single file changed in a commit
+#[derive(Clone)]
+pub struct FileStats {
+    pub path: String,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub binary: bool,
+    pub renamed: bool,  // moved or copied from another file, so only the edits made on top are counted
+}
+
+// line counts for a single commit once ignored files have been dropped
+#[derive(Default)]
+pub struct CommitStats {
+    pub insertions: usize,
+    pub deletions: usize,
+    pub binary: usize,  // binary files changed, these have no meaningful line count
+    pub renames: usize,
+    pub formatting: usize,  // lines changed by a formatting-only commit (only used if shown separately)
+    pub reverted: usize,    // lines changed by a commit that was reverted, or by the revert itself
+    pub test_insertions: usize,
+    pub test_deletions: usize,
+    pub files: Vec<FileStats>,
+}
+
+impl CommitStats {
+    fn add_file(&mut self, file: FileStats) {
+        self.insertions += file.insertions;
+        self.deletions += file.deletions;
+        self.binary += file.binary as usize;
+        self.renames += file.renamed as usize;
+        self.files.push(file);
+    }
+
+    // counts the lines changed as formatting rather than as lines added/deleted
+    pub fn move_to_formatting(&mut self) {
+        self.formatting = self.take_lines();
+    }
+
+    // counts the lines changed as reverted rather than as lines added/deleted
+    pub fn move_to_reverted(&mut self) {
+        self.reverted = self.take_lines();
+    }
+
+    // splits out the lines changed in test code (files matching any of the test patterns)
+    pub fn count_tests(&mut self, tests: &[String]) {
+        for file in &self.files {
+            if tests.iter().any(|t| matches_pattern(&file.path, t)) {
+                self.test_insertions += file.insertions;
+                self.test_deletions += file.deletions;
+            }
+        }
+    }
+
+    // clears the line counts (including each file's, so breakdowns etc. agree with the totals) and returns
+    // how many lines there were
+    fn take_lines(&mut self) -> usize {
+        let lines = self.insertions + self.deletions;
+        self.insertions = 0;
+        self.deletions = 0;
+        self.test_insertions = 0;
+        self.test_deletions = 0;
+        for file in &mut self.files {
+            file.insertions = 0;
+            file.deletions = 0;
+        }
+        lines
+    }
+}
+
+// running totals for each author/alias shown in the results table
+#[derive(Default)]
+pub struct AuthorStats {
+    pub commits: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub binary: usize,
+    pub renames: usize,
+    pub formatting: usize,
+    pub reverted: usize,
+    pub surviving: usize,   // lines at the blamed revision last changed by this author
+    pub test_insertions: usize,
+    pub test_deletions: usize,
+    pub self_churn: usize,  // lines they added then rewrote/deleted themselves soon after
+    pub reworked: usize,    // lines they added that someone else rewrote/deleted soon after
+    pub minutes: usize,     // estimated time spent, from grouping their commits into sessions
+    pub lines: Vec<usize>,  // lines modified in each commit (used for the median)
+}
+
+impl AuthorStats {
+    pub fn add_commit(&mut self, stats: &CommitStats) {
+        self.commits += 1;
+        self.insertions += stats.insertions;
+        self.deletions += stats.deletions;
+        self.binary += stats.binary;
+        self.renames += stats.renames;
+        self.formatting += stats.formatting;
+        self.reverted += stats.reverted;
+        self.test_insertions += stats.test_insertions;
+        self.test_deletions += stats.test_deletions;
+        self.lines.push(stats.insertions + stats.deletions);
+    }
+}
+
+// diffs two trees (old is None for root commits, so we diff against the empty tree) and, unless it
+// is turned off, matches up renamed/copied files so they aren't counted as a whole delete plus a whole add
+pub fn tree_diff<'a>(repo: &'a Repository, old: Option<&Tree>, new: &Tree, settings: &DiffSettings) -> Result<Diff<'a>, Error> {
+    let mut opts = DiffOptions::new();
+    for kind in &settings.whitespace {
+        match kind.as_str() {
+            "all" => opts.ignore_whitespace(true),
+            "change" => opts.ignore_whitespace_change(true),
+            "eol" => opts.ignore_whitespace_eol(true),
+            "blank" => opts.ignore_blank_lines(true),
+            _ => &mut opts,
+        };
+    }
+    let mut diff = repo.diff_tree_to_tree(old, Some(new), Some(&mut opts))?;
+    if let Some(threshold) = settings.similarity {
+        diff.find_similar(Some(DiffFindOptions::new()
+            .renames(true)
+            .copies(true)
+            .rename_threshold(threshold)
+            .copy_threshold(threshold)))?;
+    }
+
+    Ok(diff)
+}
+
+// goes through each file in the diff rather than using diff.stats() so that we can skip ignored
+// files and count binary files separately
+pub fn commit_stats(repo: &Repository, diff: &Diff, settings: &DiffSettings) -> Result<CommitStats, Error> {
+    let mut stats = CommitStats::default();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let path = match delta.new_file().path().or(delta.old_file().path()) {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => continue,
+        };
+        if is_ignored(repo, &path, &settings.ignore) {
+            continue;
+        }
+
+        let renamed = matches!(delta.status(), Delta::Renamed | Delta::Copied);
+        match Patch::from_diff(diff, idx)? {  // loading the patch is what tells us if the file is binary
+            Some(patch) if !patch.delta().flags().is_binary() && !is_binary_attr(repo, &path) => {
+                let (_, insertions, deletions) = patch.line_stats()?;
+                stats.add_file(FileStats { path, insertions, deletions, binary: false, renamed });
+            },
+            _ => stats.add_file(FileStats { path, insertions: 0, deletions: 0, binary: true, renamed }),
+        }
+    }
+
+    Ok(stats)
+}
+
+// for a merge commit, only counts changes that don't come from any of the parents (ie. conflict
+// resolutions and 'evil merges'), like 'git diff --cc' - a line only counts as added if it is new compared to
+// every parent, and as deleted if every parent had it (matched by content, since the parents' line numbers
+// don't line up). Files where each branch changed different parts merge cleanly and so count for nothing
+pub fn merge_resolution_stats(repo: &Repository, commit: &Commit, settings: &DiffSettings) -> Result<CommitStats, Error> {
+    let tree = commit.tree()?;
+    let mut per_parent: Vec<(CommitStats, HashMap<String, ChangedLines>)> = vec![];
+    for parent in commit.parents() {
+        let diff = tree_diff(repo, Some(&parent.tree()?), &tree, settings)?;
+        per_parent.push((commit_stats(repo, &diff, settings)?, changed_lines(&diff)?));
+    }
+
+    let mut stats = CommitStats::default();
+    if let Some(((first, _), _)) = per_parent.split_first() {
+        for file in &first.files {
+            // same as one of the parents, so nothing was resolved here
+            let changes: Vec<&ChangedLines> = match per_parent.iter().map(|(_, lines)| lines.get(&file.path)).collect() {
+                Some(changes) => changes,
+                None => continue,
+            };
+            let renamed = per_parent.iter().all(|(s, _)| s.files.iter().any(|f| f.path == file.path && f.renamed));
+            if file.binary {
+                stats.add_file(FileStats { renamed, ..file.clone() });
+                continue;
+            }
+
+            let (added, deleted) = changes[0];
+            let insertions = added.iter().filter(|line| changes.iter().all(|(a, _)| a.contains(line))).count();
+            let deletions = deleted.iter()
+                .map(|(content, count)| changes.iter().map(|(_, d)| d.get(content).copied().unwrap_or(0)).min().unwrap_or(0).min(*count))
+                .sum();
+            if insertions + deletions > 0 {
+                stats.add_file(FileStats { path: file.path.clone(), insertions, deletions, binary: false, renamed });
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+// (line numbers of added lines in the new file, content of deleted lines -> how many times it was deleted)
+type ChangedLines = (HashSet<u32>, HashMap<Vec<u8>, usize>);
+
+// the lines added and deleted in each file of a diff
+fn changed_lines(diff: &Diff) -> Result<HashMap<String, ChangedLines>, Error> {
+    let mut files: HashMap<String, ChangedLines> = HashMap::new();
+    for idx in 0..diff.deltas().len() {
+        let patch = match Patch::from_diff(diff, idx)? {
+            Some(patch) => patch,
+            None => continue,
+        };
+        let path = match patch.delta().new_file().path().or(patch.delta().old_file().path()) {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => continue,
+        };
+        let (added, deleted) = files.entry(path).or_default();
+        for hunk in 0..patch.num_hunks() {
+            for line in 0..patch.num_lines_in_hunk(hunk)? {
+                let line = patch.line_in_hunk(hunk, line)?;
+                match (line.origin(), line.new_lineno()) {
+                    ('+', Some(lineno)) => { added.insert(lineno); },
+                    ('-', _) => *deleted.entry(line.content().to_vec()).or_insert(0) += 1,
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+// a commit is formatting only (eg. a cargo fmt or prettier run) if every file it touches was only
+// modified, and the file is the same as before once normalised (see normalise_code)
+pub fn is_formatting_only(repo: &Repository, diff: &Diff, settings: &DiffSettings) -> Result<bool, Error> {
+    let mut checked = 0;
+    for delta in diff.deltas() {
+        let path = match delta.new_file().path() {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => return Ok(false),
+        };
+        if is_ignored(repo, &path, &settings.ignore) {
+            continue;
+        }
+        if delta.status() != Delta::Modified {  // adding, deleting or moving files is more than formatting
+            return Ok(false);
+        }
+
+        let old = repo.find_blob(delta.old_file().id())?;
+        let new = repo.find_blob(delta.new_file().id())?;
+        if old.is_binary() || new.is_binary() {
+            return Ok(false);
+        }
+        if normalise_code(old.content(), &path) != normalise_code(new.content(), &path) {
+            return Ok(false);
+        }
+        checked += 1;
+    }
+
+    Ok(checked > 0)
+}
+
+// what's left of some code once the things formatters change are taken out - whitespace and line breaks
+// (except inside string literals), trailing commas at the end of a line before a closing bracket, and
+// whether strings use single, double or backtick quotes. Comments are kept but aren't read for strings or
+// commas. Which quotes start strings and how comments look depends on the language (from the file extension).
+// Reordered imports and added/removed brackets aren't spotted
+fn normalise_code(content: &[u8], path: &str) -> Vec<u8> {
+    let ext = path.rsplit('/').next().and_then(|name| name.rsplit_once('.')).map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+    let single_quotes = SINGLE_QUOTE_STRINGS.contains(&ext.as_str());
+    let hash_comments = HASH_COMMENTS.contains(&ext.as_str());
+
+    let mut normalised: Vec<u8> = vec![];
+    let mut quote: Option<u8> = None;  // the quote the current string literal started with
+    let mut escaped = false;
+    let mut comment: Option<&[u8]> = None;  // what ends the current comment
+    let mut trailing_comma: Option<bool> = None;  // the last thing kept was a comma outside a string (and whether a line break followed it)
+    let mut i = 0;
+    while i < content.len() {
+        let c = content[i];
+        let rest = &content[i..];
+        i += 1;
+        if let Some(end) = comment {
+            if rest.starts_with(end) {
+                comment = None;
+                i += end.len() - 1;
+                normalised.extend(end.iter().filter(|b| !b.is_ascii_whitespace()));
+            } else if !c.is_ascii_whitespace() {
+                normalised.push(c);
+            }
+            continue;
+        }
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == b'\\' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                    normalised.push(b'"');
+                    continue;
+                }
+                normalised.push(c);
+            },
+            None if c.is_ascii_whitespace() => {
+                if c == b'\n' && trailing_comma.is_some() {
+                    trailing_comma = Some(true);
+                }
+            },
+            None => {
+                if trailing_comma == Some(true) && matches!(c, b')' | b']' | b'}') {
+                    normalised.pop();
+                }
+                trailing_comma = if c == b',' { Some(false) } else { None };
+                if (hash_comments && c == b'#') || (!hash_comments && rest.starts_with(b"//")) {
+                    comment = Some(b"\n");
+                    normalised.push(c);
+                } else if !hash_comments && rest.starts_with(b"/*") {
+                    comment = Some(b"*/");
+                    normalised.extend_from_slice(b"/*");
+                    i += 1;  // so '/*/' doesn't end the comment straight away
+                } else if matches!(c, b'"' | b'`') || (single_quotes && c == b'\'') {
+                    quote = Some(c);
+                    normalised.push(b'"');
+                } else if c == b'\'' {
+                    // a character literal like '"' in languages where ' doesn't start a string (anything
+                    // else, like a Rust lifetime, is just kept)
+                    let len = match rest.get(1) {
+                        Some(b'\\') => rest.iter().skip(3).position(|&b| b == b'\'').map(|p| p + 4),
+                        Some(&b) => {
+                            let char_len = match b { 0..=0x7f => 1, 0xc0..=0xdf => 2, 0xe0..=0xef => 3, _ => 4 };
+                            (rest.get(char_len + 1) == Some(&b'\'')).then_some(char_len + 2)
+                        },
+                        None => None,
+                    }.unwrap_or(1);
+                    normalised.extend_from_slice(&rest[..len]);
+                    i += len - 1;
+                } else {
+                    normalised.push(c);
+                }
+            },
+        }
+    }
+
+    normalised
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_repo::TestRepo;
+    use git2::Oid;
+
+    fn settings() -> DiffSettings {
+        DiffSettings { ignore: vec![], similarity: Some(50), whitespace: vec![] }
+    }
+
+    const BASE: &str = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+
+    #[test]
+    fn clean_merge_of_different_hunks_resolves_nothing() {
+        let mut t = TestRepo::new("clean-merge");
+        let base = t.commit("alice", &[], &[("f.txt", Some(BASE))], "base");
+        let ours = t.commit("alice", &[base], &[("f.txt", Some("a\nB\nc\nd\ne\nf\ng\nh\ni\nj\n"))], "ours");
+        let theirs = t.commit("bob", &[base], &[("f.txt", Some("a\nb\nc\nd\ne\nf\ng\nh\nI\nj\n"))], "theirs");
+        let merge = t.commit("carol", &[ours, theirs], &[("f.txt", Some("a\nB\nc\nd\ne\nf\ng\nh\nI\nj\n"))], "merge");
+
+        let stats = merge_resolution_stats(&t.repo, &t.repo.find_commit(merge).unwrap(), &settings()).unwrap();
+        assert_eq!((stats.insertions, stats.deletions), (0, 0));
+        assert!(stats.files.is_empty());
+    }
+
+    #[test]
+    fn conflict_resolution_counts_only_new_lines() {
+        let mut t = TestRepo::new("conflict-merge");
+        let base = t.commit("alice", &[], &[("f.txt", Some(BASE))], "base");
+        let ours = t.commit("alice", &[base], &[("f.txt", Some("a\nX\nc\nd\ne\nf\ng\nh\ni\nj\n"))], "ours");
+        let theirs = t.commit("bob", &[base], &[("f.txt", Some("a\nY\nc\nd\ne\nf\ng\nh\ni\nJ\n"))], "theirs");
+        let merge = t.commit("carol", &[ours, theirs], &[("f.txt", Some("a\nZ\nc\nd\ne\nf\ng\nh\ni\nJ\n"))], "merge");
+
+        let stats = merge_resolution_stats(&t.repo, &t.repo.find_commit(merge).unwrap(), &settings()).unwrap();
+        assert_eq!((stats.insertions, stats.deletions), (1, 0));  // only Z is new, J came from theirs
+    }
+
+    #[test]
+    fn evil_merge_counts_lines_from_neither_parent() {
+        let mut t = TestRepo::new("evil-merge");
+        let base = t.commit("alice", &[], &[("f.txt", Some(BASE))], "base");
+        let ours = t.commit("alice", &[base], &[("g.txt", Some("g\n"))], "ours");
+        let theirs = t.commit("bob", &[base], &[("h.txt", Some("h\n"))], "theirs");
+        let merge = t.commit("carol", &[ours, theirs], &[("h.txt", Some("h\n")), ("f.txt", Some("a\nb\nc\nd\ne\nf\ng\nh\ni\n"))], "merge");
+
+        let stats = merge_resolution_stats(&t.repo, &t.repo.find_commit(merge).unwrap(), &settings()).unwrap();
+        assert_eq!((stats.insertions, stats.deletions), (0, 1));  // j was in both parents and dropped in the merge
+    }
+
+    #[test]
+    fn moved_lines_are_taken_from_files_too() {
+        let mut stats = CommitStats::default();
+        stats.add_file(FileStats { path: "src/a.rs".to_string(), insertions: 3, deletions: 1, binary: false, renamed: false });
+        stats.add_file(FileStats { path: "tests/b.rs".to_string(), insertions: 2, deletions: 0, binary: false, renamed: false });
+        stats.count_tests(&["tests/".to_string()]);
+        stats.move_to_formatting();
+
+        assert_eq!((stats.insertions, stats.deletions, stats.test_insertions, stats.formatting), (0, 0, 0, 6));
+        assert!(stats.files.iter().all(|f| f.insertions == 0 && f.deletions == 0));
+    }
+
+    #[test]
+    fn reverted_formatting_is_only_counted_once() {
+        let mut stats = CommitStats::default();
+        stats.add_file(FileStats { path: "a.rs".to_string(), insertions: 4, deletions: 4, binary: false, renamed: false });
+        stats.move_to_formatting();  // with --formatting separate and --reverts separate, formatting comes first
+        stats.move_to_reverted();
+
+        assert_eq!((stats.insertions, stats.deletions, stats.formatting, stats.reverted), (0, 0, 8, 0));
+    }
+
+    #[test]
+    fn reverted_merge_resolution_moves_only_resolved_lines() {
+        let mut t = TestRepo::new("reverted-merge");
+        let base = t.commit("alice", &[], &[("f.txt", Some(BASE))], "base");
+        let ours = t.commit("alice", &[base], &[("f.txt", Some("a\nX\nc\nd\ne\nf\ng\nh\ni\nj\n"))], "ours");
+        let theirs = t.commit("bob", &[base], &[("f.txt", Some("a\nY\nc\nd\ne\nf\ng\nh\ni\nJ\n"))], "theirs");
+        let merge = t.commit("carol", &[ours, theirs], &[("f.txt", Some("a\nZ\nc\nd\ne\nf\ng\nh\ni\nJ\n"))], "merge");
+
+        // --merges resolutions with --reverts separate, for a merge that was reverted
+        let mut stats = merge_resolution_stats(&t.repo, &t.repo.find_commit(merge).unwrap(), &settings()).unwrap();
+        stats.move_to_reverted();
+        assert_eq!((stats.insertions, stats.deletions, stats.reverted), (0, 0, 1));
+        assert!(stats.files.iter().all(|f| f.insertions == 0 && f.deletions == 0));
+    }
+
+    fn formatting_only(path: &str, old: &str, new: &str) -> bool {
+        let mut t = TestRepo::new("formatting");
+        let before = t.commit("alice", &[], &[(path, Some(old))], "before");
+        let after = t.commit("alice", &[before], &[(path, Some(new))], "after");
+        let old_tree = t.repo.find_commit(before).unwrap().tree().unwrap();
+        let new_tree = t.repo.find_commit(after).unwrap().tree().unwrap();
+        let diff = tree_diff(&t.repo, Some(&old_tree), &new_tree, &settings()).unwrap();
+        is_formatting_only(&t.repo, &diff, &settings()).unwrap()
+    }
+
+    #[test]
+    fn formatter_changes_are_formatting() {
+        assert!(formatting_only("f.js", "f(a,b)\n", "f(a, b)\n"));
+        assert!(formatting_only("f.js", "let x = {a: 1, b: 2};\n", "let x = {\n  a: 1,\n  b: 2,\n};\n"));
+        assert!(formatting_only("f.js", "import x from 'y';\n", "import x from \"y\";\n"));
+    }
+
+    #[test]
+    fn other_changes_are_not_formatting() {
+        assert!(!formatting_only("f.js", "s = \"a b\"\n", "s = \"a  b\"\n"));  // whitespace inside a string matters
+        assert!(!formatting_only("f.js", "f(a, b)\n", "f(a, c)\n"));
+        assert!(!formatting_only("f.js", "f(a, b)\n", "f(b, a)\n"));
+        assert!(!formatting_only("f.py", "x = (a,)\n", "x = (a)\n"));  // a tuple isn't a plain value
+    }
+
+    #[test]
+    fn quotes_in_lifetimes_and_comments_dont_start_strings() {
+        assert!(formatting_only("f.rs", "fn f<'a>(x:&'a str) {}\n", "fn f<'a>(x: &'a str) {}\n"));
+        assert!(formatting_only("f.js", "// don't\nf(a,b)\n", "// don't\nf(a, b)\n"));
+        assert!(formatting_only("f.py", "# don't\nf(a,b)\n", "# don't\nf(a, b)\n"));
+        assert!(formatting_only("f.rs", "let q = '\"';\nf(a,b)\n", "let q = '\"';\nf(a, b)\n"));
+        assert!(!formatting_only("f.js", "// don't\nf(a, b)\n", "// do\nf(a, b)\n"));  // comments still count
+    }
+
+    // --merges first-parent diffs a merge against its first parent, like any other commit
+    fn merge_is_formatting_only(t: &TestRepo, merge: Oid) -> bool {
+        let merge = t.repo.find_commit(merge).unwrap();
+        let diff = tree_diff(&t.repo, Some(&merge.parent(0).unwrap().tree().unwrap()), &merge.tree().unwrap(), &settings()).unwrap();
+        is_formatting_only(&t.repo, &diff, &settings()).unwrap()
+    }
+
+    #[test]
+    fn first_parent_merge_of_a_formatting_branch_is_formatting() {
+        let mut t = TestRepo::new("formatting-merge");
+        let base = t.commit("alice", &[], &[("f.js", Some("f(a,b)\n"))], "base");
+        let main = t.commit("alice", &[base], &[("g.js", Some("g()\n"))], "main");
+        let fmt = t.commit("bob", &[base], &[("f.js", Some("f(a, b)\n"))], "fmt");
+        let merge = t.commit("carol", &[main, fmt], &[("f.js", Some("f(a, b)\n"))], "merge fmt");
+        assert!(merge_is_formatting_only(&t, merge));
+
+        let feature = t.commit("bob", &[fmt], &[("h.js", Some("h()\n"))], "feature");
+        let merge = t.commit("carol", &[merge, feature], &[("h.js", Some("h()\n"))], "merge feature");
+        assert!(!merge_is_formatting_only(&t, merge));  // the branch brings in real changes
+    }
+
+    #[test]
+    fn normalise_code_keeps_escaped_quotes_in_strings() {
+        assert_eq!(normalise_code(b"x = 'it\\'s' ,\n)", "f.js"), b"x=\"it\\'s\")".to_vec());
+    }
+}
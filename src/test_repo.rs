@@ -0,0 +1,48 @@
+// throwaway repos for the tests, built straight from trees so commits can have any parents and times
+use git2::{Repository, Oid, Signature, Time, FileMode, build::TreeUpdateBuilder};
+use std::env;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static REPOS: AtomicUsize = AtomicUsize::new(0);  // so repos with the same name in one run don't clash
+
+pub struct TestRepo {
+    pub repo: Repository,
+    time: i64,
+}
+
+impl TestRepo {
+    // a new empty repo in the temp directory
+    pub fn new(name: &str) -> Self {
+        let n = REPOS.fetch_add(1, Ordering::SeqCst);
+        let path = env::temp_dir().join(format!("waw-test-{}-{}-{}", name, std::process::id(), n));
+        let _ = fs::remove_dir_all(&path);
+        TestRepo { repo: Repository::init(&path).unwrap(), time: 1_800_000_000 }
+    }
+
+    // commits the first parent's files (or nothing) with the given files changed - None deletes a file
+    pub fn commit(&mut self, author: &str, parents: &[Oid], files: &[(&str, Option<&str>)], message: &str) -> Oid {
+        let parents: Vec<_> = parents.iter().map(|p| self.repo.find_commit(*p).unwrap()).collect();
+        let base = match parents.first() {
+            Some(parent) => parent.tree().unwrap(),
+            None => self.repo.find_tree(self.repo.treebuilder(None).unwrap().write().unwrap()).unwrap(),
+        };
+        let mut update = TreeUpdateBuilder::new();
+        for (path, content) in files {
+            match content {
+                Some(content) => update.upsert(*path, self.repo.blob(content.as_bytes()).unwrap(), FileMode::Blob),
+                None => update.remove(*path),
+            };
+        }
+        let tree = self.repo.find_tree(update.create_updated(&self.repo, &base).unwrap()).unwrap();
+
+        self.time += 60 * 60;  // an hour after the last commit
+        let signature = Signature::new(author, &format!("{}@example.com", author), &Time::new(self.time, 0)).unwrap();
+        let parents: Vec<_> = parents.iter().collect();
+        let id = self.repo.commit(None, &signature, &signature, message, &tree, &parents).unwrap();
+        self.repo.reference("refs/heads/main", id, true, "test").unwrap();
+        self.repo.set_head("refs/heads/main").unwrap();
+
+        id
+    }
+}
@@ -0,0 +1,211 @@
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Timelike, Weekday};
+use git2::Oid;
+use colored::Colorize;
+use std::collections::HashMap;
+
+// a single counted commit, for working out when people work
+pub struct Activity {
+    pub commit: Oid,  // so a commit credited to several authors can be counted once for the team
+    pub time: i64,    // author time (when the work was written rather than when it was last rebased etc.)
+    pub offset: i32,  // author's timezone offset in minutes
+    pub lines: usize,
+}
+
+// author -> everything they did
+pub type ActivityLog = HashMap<String, Vec<Activity>>;
+
+// the time of a commit in the author's own timezone
+pub fn local_time(activity: &Activity) -> DateTime<FixedOffset> {
+    let offset = FixedOffset::east_opt(activity.offset * 60).unwrap_or(FixedOffset::east_opt(0).unwrap());
+    DateTime::from_timestamp(activity.time, 0).unwrap_or_default().with_timezone(&offset)
+}
+
+// the day/week/month a date falls in
+pub fn period_key(date: NaiveDate, period: &str) -> String {
+    match period {
+        "day" => date.format("%Y-%m-%d").to_string(),
+        "week" => format!("{}-W{:02}", date.iso_week().year(), date.iso_week().week()),
+        _ => date.format("%Y-%m").to_string(),
+    }
+}
+
+// every period between the first and last commit (including ones where nobody did anything) in order
+fn all_periods(log: &ActivityLog, period: &str) -> Vec<String> {
+    let dates: Vec<NaiveDate> = log.values().flatten().map(|a| local_time(a).date_naive()).collect();
+    let (first, last) = match (dates.iter().min(), dates.iter().max()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return vec![],
+    };
+
+    let mut periods: Vec<String> = vec![];
+    let mut date = first;
+    while date <= last {
+        let key = period_key(date, period);
+        if periods.last() != Some(&key) {
+            periods.push(key);
+        }
+        date = match date.succ_opt() {
+            Some(next) => next,
+            None => break,
+        };
+    }
+
+    periods
+}
+
+// prints each author's commits and lines per day/week/month as a table, sparklines or bar charts
+pub fn print_timeline(log: &ActivityLog, period: &str, format: &str) {
+    let periods = all_periods(log, period);
+    let mut authors: Vec<&String> = log.keys().collect();
+    authors.sort();
+
+    // author -> (period -> (commits, lines))
+    let mut buckets: HashMap<&String, HashMap<String, (usize, usize)>> = HashMap::new();
+    for (author, activities) in log {
+        for activity in activities {
+            let bucket = buckets.entry(author).or_default()
+                .entry(period_key(local_time(activity).date_naive(), period))
+                .or_insert((0, 0));
+            bucket.0 += 1;
+            bucket.1 += activity.lines;
+        }
+    }
+    let get = |author: &String, key: &String| buckets.get(author).and_then(|b| b.get(key)).copied().unwrap_or((0, 0));
+
+    match format {
+        "spark" => {  // one line per author, with a block for each period scaled to their busiest period
+            println!();
+            println!("{} to {}", periods.first().unwrap_or(&String::new()), periods.last().unwrap_or(&String::new()));
+            println!("{0: <20} | {1: <10} | {2}", "author".yellow(), "".yellow(), "activity".yellow());
+            for author in authors {
+                let commits: Vec<usize> = periods.iter().map(|p| get(author, p).0).collect();
+                let lines: Vec<usize> = periods.iter().map(|p| get(author, p).1).collect();
+                println!("{0: <20} | {1: <10} | {2}", author, "commits", sparkline(&commits));
+                println!("{0: <20} | {1: <10} | {2}", "", "lines", sparkline(&lines));
+            }
+        },
+        "bars" => {  // a bar chart of lines for each author, with bars scaled to the busiest period overall
+            let max = authors.iter().flat_map(|a| periods.iter().map(|p| get(a, p).1)).max().unwrap_or(0).max(1);
+            for author in authors {
+                println!();
+                println!("{}", author.yellow());
+                for key in &periods {
+                    let (commits, lines) = get(author, key);
+                    let bar = "█".repeat((lines * 40).div_ceil(max));
+                    println!("{0: <10} | {1: <40} | {2} commits, {3} lines", key, bar, commits, lines);
+                }
+            }
+        },
+        _ => {  // a row per period with 'commits / lines' for each author
+            let width = 10 + authors.len() * 23 + 1;
+            println!();
+            println!("{:-<width$}", "");
+            print!("{: <10}", period.yellow());
+            for author in &authors {
+                print!(" | {: <20}", author.yellow());
+            }
+            println!();
+            println!("{:-<width$}", "");
+            for key in &periods {
+                print!("{: <10}", key);
+                for author in &authors {
+                    let (commits, lines) = get(author, key);
+                    print!(" | {: <20}", format!("{} / {}", commits, lines));
+                }
+                println!();
+            }
+        },
+    }
+}
+
+// rough minutes of work behind some commits - commits less than gap minutes apart are in the same session
+// and count the time between them, and each session gets allowance minutes for the work before its first commit
+pub fn estimate_minutes(activities: &[Activity], gap: i64, allowance: i64) -> usize {
+    let mut times: Vec<i64> = activities.iter().map(|a| a.time).collect();
+    times.sort();
+    let mut minutes = 0;
+    for (i, time) in times.iter().enumerate() {
+        minutes += match i.checked_sub(1).map(|prev| time - times[prev]) {
+            Some(since) if since < gap * 60 => since / 60,
+            _ => allowance,
+        };
+    }
+
+    minutes as usize
+}
+
+// prints when each author commits by hour of day and day of week (in their own timezone), with the share
+// of their commits made at weekends and late at night (between 22:00 and 06:00)
+pub fn print_work_patterns(log: &ActivityLog) {
+    let mut authors: Vec<&String> = log.keys().collect();
+    authors.sort();
+
+    let width = 20 + 3 + 24 + 3 + 7 + 3 + 10 + 3 + 10 + 1;
+    println!();
+    println!("{:-<width$}", "");
+    println!("{0: <20} | {1: <24} | {2: <7} | {3: <10} | {4: <10}",
+        "author".yellow(), "hour of day".yellow(), "weekday".yellow(), "weekend".yellow(), "late night".yellow());
+    println!("{0: <20} | {1: <24} | {2: <7} | {3: <10} | {4: <10}", "", "0     6     12    18    ", "MTWTFSS", "", "");
+    println!("{:-<width$}", "");
+    for author in authors {
+        let mut hours = [0; 24];
+        let mut weekdays = [0; 7];
+        let (mut weekend, mut late) = (0, 0);
+        for activity in &log[author] {
+            let time = local_time(activity);
+            hours[time.hour() as usize] += 1;
+            weekdays[time.weekday().num_days_from_monday() as usize] += 1;
+            if matches!(time.weekday(), Weekday::Sat | Weekday::Sun) {
+                weekend += 1;
+            }
+            if time.hour() >= 22 || time.hour() < 6 {
+                late += 1;
+            }
+        }
+        let share = |count: usize| format!("{:.0}%", count as f64 / log[author].len().max(1) as f64 * 100.0);
+        println!("{0: <20} | {1: <24} | {2: <7} | {3: <10} | {4: <10}",
+            author, sparkline(&hours), sparkline(&weekdays), share(weekend), share(late));
+    }
+}
+
+// turns counts into a string of block characters, scaled so the biggest is a full block
+fn sparkline(values: &[usize]) -> String {
+    let blocks = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().max().copied().unwrap_or(0).max(1);
+    values.iter()
+        .map(|&v| blocks[(v * (blocks.len() - 1)).div_ceil(max)])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activities(times: &[i64]) -> Vec<Activity> {
+        times.iter().map(|t| Activity { commit: Oid::zero(), time: *t, offset: 0, lines: 1 }).collect()
+    }
+
+    #[test]
+    fn sessions_count_time_between_commits() {
+        // two commits 30 minutes apart: the allowance for the first, then the gap
+        assert_eq!(estimate_minutes(&activities(&[1800, 0]), 120, 30), 60);
+        // a gap of 2 hours or more starts a new session
+        assert_eq!(estimate_minutes(&activities(&[0, 1800, 1800 + 7200]), 120, 30), 30 + 30 + 30);
+        assert_eq!(estimate_minutes(&activities(&[0, 7199]), 120, 30), 30 + 119);
+        assert_eq!(estimate_minutes(&[], 120, 30), 0);
+    }
+
+    #[test]
+    fn local_time_uses_the_authors_offset() {
+        let activity = Activity { commit: Oid::zero(), time: 1_800_000_000, offset: -300, lines: 1 };
+        assert_eq!(local_time(&activity).to_rfc3339(), "2027-01-15T03:00:00-05:00");
+    }
+
+    #[test]
+    fn periods_use_iso_weeks() {
+        let date = NaiveDate::from_ymd_opt(2027, 1, 1).unwrap();
+        assert_eq!(period_key(date, "day"), "2027-01-01");
+        assert_eq!(period_key(date, "week"), "2026-W53");
+        assert_eq!(period_key(date, "month"), "2027-01");
+    }
+}